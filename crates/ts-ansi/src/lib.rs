@@ -5,5 +5,16 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod style;
+mod styling;
+mod width;
+mod wrap;
+
+#[cfg(feature = "std")]
+pub use styling::{Stream, should_style};
+pub use styling::{StripStyles, set_styling, styled, styling_enabled, write_styled};
+pub use width::{truncate_visible, visible_width};
+pub use wrap::wrap;
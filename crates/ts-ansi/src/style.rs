@@ -1,5 +1,13 @@
 //! ANSI codes
 
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::styling_enabled;
+
 /// Format an error message
 #[macro_export]
 #[clippy::format_args]
@@ -28,6 +36,20 @@ macro_rules! format_failure {
     ($($arg:tt)*) => (::core::format_args!("{}{}Failure{}:{} {}",$crate::style::BOLD, $crate::style::RED, $crate::style::DEFAULT, $crate::style::RESET,  ::core::format_args!($($arg)*)))
 }
 
+/// Format a note message
+#[macro_export]
+#[clippy::format_args]
+macro_rules! format_note {
+    ($($arg:tt)*) => (::core::format_args!("{}{}note{}:{} {}",$crate::style::BOLD, $crate::style::CYAN, $crate::style::DEFAULT, $crate::style::RESET,  ::core::format_args!($($arg)*)))
+}
+
+/// Format a help message
+#[macro_export]
+#[clippy::format_args]
+macro_rules! format_help {
+    ($($arg:tt)*) => (::core::format_args!("{}{}help{}:{} {}",$crate::style::BOLD, $crate::style::GREEN, $crate::style::DEFAULT, $crate::style::RESET,  ::core::format_args!($($arg)*)))
+}
+
 /// Reset styling
 pub const RESET: &str = "\x1b[0m";
 
@@ -41,6 +63,21 @@ pub const DIM: &str = "\x1b[2m";
 /// Following text will NOT be dim
 pub const NO_DIM: &str = "\x1b[22m";
 
+/// Following text will be italic
+pub const ITALIC: &str = "\x1b[3m";
+/// Following text will NOT be italic
+pub const NO_ITALIC: &str = "\x1b[23m";
+
+/// Following text will be underlined
+pub const UNDERLINE: &str = "\x1b[4m";
+/// Following text will NOT be underlined
+pub const NO_UNDERLINE: &str = "\x1b[24m";
+
+/// Following text will be struck through
+pub const STRIKETHROUGH: &str = "\x1b[9m";
+/// Following text will NOT be struck through
+pub const NO_STRIKETHROUGH: &str = "\x1b[29m";
+
 /// Set colour of text to black
 pub const BLACK: &str = "\x1b[90m";
 /// Set background of text to black
@@ -128,3 +165,491 @@ pub const LINE_START: &str = "\x1b[1G";
 pub const ERASE_LINE: &str = "\x1b[0K";
 /// Move to previous line
 pub const LINE_UP: &str = "\x1b[1A";
+/// Hide the cursor
+pub const HIDE_CURSOR: &str = "\x1b[?25l";
+/// Show the cursor
+pub const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// A truecolor RGB colour, for terminals that support 24-bit colour.
+///
+/// ## Usage
+/// ```
+/// use ts_ansi::style::{RESET, Rgb};
+///
+/// let banner = format!("{}ts-lib{RESET}", Rgb(255, 128, 0).fg());
+/// assert_eq!("\x1b[38;2;255;128;0mts-lib\x1b[0m", banner);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+impl Rgb {
+    /// Returns the code to set the background of text to this colour.
+    pub fn bg(self) -> String {
+        format!("\x1b[48;2;{};{};{}m", self.0, self.1, self.2)
+    }
+
+    /// Returns the code to set the colour of text to this colour.
+    pub fn fg(self) -> String {
+        format!("\x1b[38;2;{};{};{}m", self.0, self.1, self.2)
+    }
+}
+
+/// A colour from the 256 colour palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ansi256(pub u8);
+impl Ansi256 {
+    /// Returns the code to set the background of text to this colour.
+    pub fn bg(self) -> String {
+        format!("\x1b[48;5;{}m", self.0)
+    }
+
+    /// Returns the code to set the colour of text to this colour.
+    pub fn fg(self) -> String {
+        format!("\x1b[38;5;{}m", self.0)
+    }
+}
+
+/// One of the named colours of the text colour constants, e.g., [`RED`] or [`DIM_BLUE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NamedColor {
+    /// [`BLACK`]
+    Black,
+    /// [`BLUE`]
+    Blue,
+    /// [`CYAN`]
+    Cyan,
+    /// [`DEFAULT`]
+    Default,
+    /// [`DIM_BLACK`]
+    DimBlack,
+    /// [`DIM_BLUE`]
+    DimBlue,
+    /// [`DIM_CYAN`]
+    DimCyan,
+    /// [`DIM_GREEN`]
+    DimGreen,
+    /// [`DIM_MAGENTA`]
+    DimMagenta,
+    /// [`DIM_RED`]
+    DimRed,
+    /// [`DIM_WHITE`]
+    DimWhite,
+    /// [`DIM_YELLOW`]
+    DimYellow,
+    /// [`GREEN`]
+    Green,
+    /// [`MAGENTA`]
+    Magenta,
+    /// [`RED`]
+    Red,
+    /// [`WHITE`]
+    White,
+    /// [`YELLOW`]
+    Yellow,
+}
+impl NamedColor {
+    /// Returns the SGR parameter to set the colour of text to this colour, e.g., `91` for [`RED`].
+    fn parameter(self) -> u8 {
+        match self {
+            Self::Black => 90,
+            Self::Blue => 94,
+            Self::Cyan => 96,
+            Self::Default => 39,
+            Self::DimBlack => 30,
+            Self::DimBlue => 34,
+            Self::DimCyan => 36,
+            Self::DimGreen => 32,
+            Self::DimMagenta => 35,
+            Self::DimRed => 31,
+            Self::DimWhite => 37,
+            Self::DimYellow => 33,
+            Self::Green => 92,
+            Self::Magenta => 95,
+            Self::Red => 91,
+            Self::White => 97,
+            Self::Yellow => 93,
+        }
+    }
+}
+
+/// Any colour a terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Color {
+    /// A colour from the 256 colour palette.
+    Ansi256(Ansi256),
+    /// One of the named colours, e.g., [`NamedColor::Red`] for [`RED`].
+    Named(NamedColor),
+    /// A truecolor RGB colour.
+    Rgb(Rgb),
+}
+impl Color {
+    /// Returns the code to set the background of text to this colour.
+    ///
+    /// The background of a named colour is the matching background constant, e.g., [`BG_RED`] for
+    /// [`RED`].
+    pub fn bg(self) -> String {
+        match self {
+            Self::Ansi256(colour) => colour.bg(),
+            Self::Named(colour) => format!("\x1b[{}m", colour.parameter() + 10),
+            Self::Rgb(colour) => colour.bg(),
+        }
+    }
+
+    /// Returns the SGR parameters to set the background of text to this colour, e.g., `48;5;9`.
+    fn bg_parameters(self) -> String {
+        match self {
+            Self::Ansi256(Ansi256(index)) => format!("48;5;{index}"),
+            Self::Named(colour) => (colour.parameter() + 10).to_string(),
+            Self::Rgb(Rgb(red, green, blue)) => format!("48;2;{red};{green};{blue}"),
+        }
+    }
+
+    /// Returns the code to set the colour of text to this colour.
+    pub fn fg(self) -> String {
+        match self {
+            Self::Ansi256(colour) => colour.fg(),
+            Self::Named(colour) => format!("\x1b[{}m", colour.parameter()),
+            Self::Rgb(colour) => colour.fg(),
+        }
+    }
+
+    /// Returns the SGR parameters to set the colour of text to this colour, e.g., `38;5;9`.
+    fn fg_parameters(self) -> String {
+        match self {
+            Self::Ansi256(Ansi256(index)) => format!("38;5;{index}"),
+            Self::Named(colour) => colour.parameter().to_string(),
+            Self::Rgb(Rgb(red, green, blue)) => format!("38;2;{red};{green};{blue}"),
+        }
+    }
+}
+impl From<NamedColor> for Color {
+    fn from(value: NamedColor) -> Self {
+        Self::Named(value)
+    }
+}
+impl From<Ansi256> for Color {
+    fn from(value: Ansi256) -> Self {
+        Self::Ansi256(value)
+    }
+}
+impl From<Rgb> for Color {
+    fn from(value: Rgb) -> Self {
+        Self::Rgb(value)
+    }
+}
+
+/// A combination of attributes and colours that is written as a single SGR sequence, e.g.,
+/// `\x1b[1;4;38;5;9m`, rather than a sequence for each attribute and colour.
+///
+/// ## Usage
+/// ```
+/// use ts_ansi::style::{Ansi256, Color, Style};
+///
+/// ts_ansi::set_styling(true);
+/// let text = Style::new().bold().underline().fg(Color::Ansi256(Ansi256(9)));
+/// assert_eq!("\x1b[1;4;38;5;9merror\x1b[0m", text.paint("error"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    /// The SGR parameters of the attributes, in the order they were added.
+    attributes: [Option<u8>; 5],
+    /// The background of the text.
+    bg: Option<Color>,
+    /// The colour of the text.
+    fg: Option<Color>,
+}
+impl Style {
+    /// Add an attribute, by its SGR parameter, if the style does not already have it.
+    fn attribute(mut self, parameter: u8) -> Self {
+        if !self.attributes.contains(&Some(parameter))
+            && let Some(slot) = self.attributes.iter_mut().find(|slot| slot.is_none())
+        {
+            *slot = Some(parameter);
+        }
+        self
+    }
+
+    /// Set the background of the text.
+    pub fn bg<C: Into<Color>>(mut self, colour: C) -> Self {
+        self.bg = Some(colour.into());
+        self
+    }
+
+    /// Make the text bold.
+    pub fn bold(self) -> Self {
+        self.attribute(1)
+    }
+
+    /// Make the text dim.
+    pub fn dim(self) -> Self {
+        self.attribute(2)
+    }
+
+    /// Set the colour of the text.
+    pub fn fg<C: Into<Color>>(mut self, colour: C) -> Self {
+        self.fg = Some(colour.into());
+        self
+    }
+
+    /// Make the text italic.
+    pub fn italic(self) -> Self {
+        self.attribute(3)
+    }
+
+    /// Create a new style without any attributes or colours.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the text wrapped in the style, the styling is always reset after the text. No
+    /// styling is written if output should not be styled, see
+    /// [`styling_enabled`](crate::styling_enabled).
+    pub fn paint(&self, text: &str) -> String {
+        self.paint_if(text, styling_enabled())
+    }
+
+    /// Returns the text wrapped in the style if `is_styled`, otherwise the text is returned
+    /// unchanged.
+    fn paint_if(&self, text: &str, is_styled: bool) -> String {
+        let sequence = self.sequence();
+        if sequence.is_empty() || !is_styled {
+            return String::from(text);
+        }
+
+        format!("{sequence}{text}{RESET}")
+    }
+
+    /// Returns the single SGR sequence for the style, or an empty string if the style is empty.
+    pub fn sequence(&self) -> String {
+        let parameters: Vec<String> = self
+            .attributes
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .chain(self.fg.map(Color::fg_parameters))
+            .chain(self.bg.map(Color::bg_parameters))
+            .collect();
+
+        if parameters.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", parameters.join(";"))
+        }
+    }
+
+    /// Strike through the text.
+    pub fn strikethrough(self) -> Self {
+        self.attribute(9)
+    }
+
+    /// Underline the text.
+    pub fn underline(self) -> Self {
+        self.attribute(4)
+    }
+}
+
+/// Text with scoped styling, the styling is always reset after the text. No styling is written if
+/// output should not be styled, see [`styling_enabled`](crate::styling_enabled).
+///
+/// ## Usage
+/// ```
+/// use ts_ansi::style::{RED, Styled};
+///
+/// ts_ansi::set_styling(true);
+/// let text = Styled::new("error").bold().fg(RED);
+/// assert_eq!("\x1b[1m\x1b[91merror\x1b[0m", text.to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Styled<'a> {
+    /// The background of the text.
+    bg: Option<&'static str>,
+    /// If the text is bold.
+    bold: bool,
+    /// If the text is dim.
+    dim: bool,
+    /// The colour of the text.
+    fg: Option<&'static str>,
+    /// The text to style.
+    text: &'a str,
+}
+impl<'a> Styled<'a> {
+    /// Set the background of the text, e.g., [`BG_RED`].
+    pub fn bg(mut self, colour: &'static str) -> Self {
+        self.bg = Some(colour);
+        self
+    }
+
+    /// Make the text bold.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Make the text dim.
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    /// Set the colour of the text, e.g., [`RED`].
+    pub fn fg(mut self, colour: &'static str) -> Self {
+        self.fg = Some(colour);
+        self
+    }
+
+    /// Create new unstyled text.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            bg: None,
+            bold: false,
+            dim: false,
+            fg: None,
+            text,
+        }
+    }
+
+    /// Write the text, with its styling if `is_styled`.
+    fn write_if(&self, f: &mut dyn core::fmt::Write, is_styled: bool) -> core::fmt::Result {
+        if !is_styled {
+            return f.write_str(self.text);
+        }
+
+        if self.bold {
+            f.write_str(BOLD)?;
+        }
+        if self.dim {
+            f.write_str(DIM)?;
+        }
+        if let Some(fg) = self.fg {
+            f.write_str(fg)?;
+        }
+        if let Some(bg) = self.bg {
+            f.write_str(bg)?;
+        }
+
+        write!(f, "{}{RESET}", self.text)
+    }
+}
+impl core::fmt::Display for Styled<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.write_if(f, styling_enabled())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{
+        format,
+        string::{String, ToString},
+    };
+
+    use crate::style::{
+        Ansi256, BG_BLACK, BG_DEFAULT, BG_DIM_BLUE, BG_RED, BOLD, CYAN, Color, DEFAULT, DIM_BLUE,
+        GREEN, NamedColor, RED, RESET, Rgb, Style, Styled,
+    };
+
+    #[test]
+    fn formats_note_and_help() {
+        let value = 3;
+
+        assert_eq!(
+            format!("{BOLD}{CYAN}note{DEFAULT}:{RESET} value is 3"),
+            format!("{}", format_note!("value is {value}"))
+        );
+        assert_eq!(
+            format!("{BOLD}{GREEN}help{DEFAULT}:{RESET} try 4"),
+            format!("{}", format_help!("try {}", value + 1))
+        );
+    }
+
+    #[test]
+    fn styles_scoped_text() {
+        let text = Styled::new("text").bold().fg(RED).bg(BG_BLACK);
+        let mut styled = String::new();
+        text.write_if(&mut styled, true)
+            .expect("writing to a string should succeed");
+        assert_eq!(format!("{BOLD}{RED}{BG_BLACK}text{RESET}"), styled);
+        assert_eq!("plain", Styled::new("plain").to_string().replace(RESET, ""));
+
+        let mut unstyled = String::new();
+        text.write_if(&mut unstyled, false)
+            .expect("writing to a string should succeed");
+        assert_eq!("text", unstyled);
+    }
+
+    #[test]
+    fn formats_colours() {
+        assert_eq!("\x1b[38;2;1;2;3m", Rgb(1, 2, 3).fg());
+        assert_eq!("\x1b[48;2;1;2;3m", Rgb(1, 2, 3).bg());
+        assert_eq!("\x1b[38;5;208m", Ansi256(208).fg());
+        assert_eq!("\x1b[48;5;208m", Ansi256(208).bg());
+
+        assert_eq!(RED, Color::Named(NamedColor::Red).fg());
+        assert_eq!(BG_RED, Color::Named(NamedColor::Red).bg());
+        assert_eq!(DIM_BLUE, Color::from(NamedColor::DimBlue).fg());
+        assert_eq!(BG_DIM_BLUE, Color::Named(NamedColor::DimBlue).bg());
+        assert_eq!(DEFAULT, Color::Named(NamedColor::Default).fg());
+        assert_eq!(BG_DEFAULT, Color::Named(NamedColor::Default).bg());
+        assert_eq!(Ansi256(208).bg(), Color::from(Ansi256(208)).bg());
+        assert_eq!(Rgb(1, 2, 3).fg(), Color::from(Rgb(1, 2, 3)).fg());
+    }
+
+    #[test]
+    fn names_colour_constants() {
+        use crate::style::{
+            BLACK, BLUE, DIM_BLACK, DIM_CYAN, DIM_GREEN, DIM_MAGENTA, DIM_RED, DIM_WHITE,
+            DIM_YELLOW, MAGENTA, WHITE, YELLOW,
+        };
+
+        for (colour, code) in [
+            (NamedColor::Black, BLACK),
+            (NamedColor::Red, RED),
+            (NamedColor::Green, GREEN),
+            (NamedColor::Yellow, YELLOW),
+            (NamedColor::Blue, BLUE),
+            (NamedColor::Magenta, MAGENTA),
+            (NamedColor::Cyan, CYAN),
+            (NamedColor::White, WHITE),
+            (NamedColor::DimBlack, DIM_BLACK),
+            (NamedColor::DimRed, DIM_RED),
+            (NamedColor::DimGreen, DIM_GREEN),
+            (NamedColor::DimYellow, DIM_YELLOW),
+            (NamedColor::DimBlue, DIM_BLUE),
+            (NamedColor::DimMagenta, DIM_MAGENTA),
+            (NamedColor::DimCyan, DIM_CYAN),
+            (NamedColor::DimWhite, DIM_WHITE),
+            (NamedColor::Default, DEFAULT),
+        ] {
+            assert_eq!(code, Color::Named(colour).fg());
+        }
+    }
+
+    #[test]
+    fn combines_style_into_one_sequence() {
+        let style = Style::new().bold().underline().fg(Ansi256(9));
+        assert_eq!("\x1b[1;4;38;5;9m", style.sequence());
+        assert_eq!("\x1b[1;4;38;5;9mtext\x1b[0m", style.paint_if("text", true));
+
+        let style = Style::new()
+            .italic()
+            .bold()
+            .bold()
+            .fg(NamedColor::Red)
+            .bg(Rgb(1, 2, 3));
+        assert_eq!("\x1b[3;1;91;48;2;1;2;3m", style.sequence());
+        assert_eq!(
+            "\x1b[2;9;101m",
+            Style::new()
+                .dim()
+                .strikethrough()
+                .bg(NamedColor::Red)
+                .sequence()
+        );
+
+        assert_eq!("", Style::new().sequence());
+        assert_eq!("plain", Style::new().paint("plain"));
+
+        assert_eq!("text", style.paint_if("text", false));
+    }
+}
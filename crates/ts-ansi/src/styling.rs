@@ -0,0 +1,219 @@
+//! Deciding if output should be styled.
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// If output should be styled.
+static STYLING: AtomicBool = AtomicBool::new(true);
+
+/// If styling has been set by [`set_styling`], overriding `should_style`.
+static STYLING_OVERRIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Where the parser of [`StripStyles`] is in an ANSI escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+enum EscapeState {
+    /// Inside a control sequence: `ESC [`.
+    ControlSequence,
+    /// Following the escape character.
+    Escape,
+    /// Outside an escape sequence.
+    Text,
+}
+
+/// A writer that removes ANSI escape sequences from everything written to it.
+#[derive(Debug)]
+pub struct StripStyles<W> {
+    /// Where the writer is in an escape sequence, sequences may be split across writes.
+    state: EscapeState,
+    /// The writer to write the unstyled text to.
+    writer: W,
+}
+impl<W: fmt::Write> StripStyles<W> {
+    /// Create a writer that removes ANSI escape sequences before writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            state: EscapeState::Text,
+            writer,
+        }
+    }
+}
+impl<W: fmt::Write> fmt::Write for StripStyles<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for character in s.chars() {
+            self.state = match (self.state, character) {
+                (EscapeState::Text, '\x1b') => EscapeState::Escape,
+                (EscapeState::Text, _) => {
+                    self.writer.write_char(character)?;
+                    EscapeState::Text
+                }
+                (EscapeState::Escape, '[') => EscapeState::ControlSequence,
+                // Control sequences end with a character in `@` to `~`.
+                (EscapeState::ControlSequence, '@'..='~') | (EscapeState::Escape, _) => {
+                    EscapeState::Text
+                }
+                (EscapeState::ControlSequence, _) => EscapeState::ControlSequence,
+            };
+        }
+
+        Ok(())
+    }
+}
+
+/// A standard output stream.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Stream {
+    /// Standard error.
+    Stderr,
+    /// Standard output.
+    Stdout,
+}
+
+/// Override if output should be styled, styling is enabled by default.
+///
+/// This takes precedence over the terminal and environment checks of `should_style`.
+pub fn set_styling(enabled: bool) {
+    STYLING.store(enabled, Ordering::Relaxed);
+    STYLING_OVERRIDDEN.store(true, Ordering::Relaxed);
+}
+
+/// Returns if output should be styled. If styling has been set by [`set_styling`], that is used,
+/// otherwise output is styled if output written to `stderr` should be styled, see `should_style`.
+///
+/// Without the `std` feature, output is styled unless styling has been disabled by
+/// [`set_styling`].
+pub fn styling_enabled() -> bool {
+    #[cfg(feature = "std")]
+    if !STYLING_OVERRIDDEN.load(Ordering::Relaxed) {
+        return should_style(Stream::Stderr);
+    }
+
+    STYLING.load(Ordering::Relaxed)
+}
+
+/// Returns an ANSI code if output should be styled, otherwise an empty string.
+///
+/// ## Usage
+/// ```
+/// use ts_ansi::{style::RED, styled};
+///
+/// let error = format!("{}error", styled(RED));
+/// ```
+pub fn styled(code: &str) -> &str {
+    if styling_enabled() { code } else { "" }
+}
+
+/// Write some styled output, removing any ANSI escape sequences if output should not be styled.
+pub fn write_styled(
+    f: &mut dyn fmt::Write,
+    write: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+) -> fmt::Result {
+    if styling_enabled() {
+        write(f)
+    } else {
+        write(&mut StripStyles::new(f))
+    }
+}
+
+/// Returns if output written to a stream should be styled.
+///
+/// * If styling has been set by [`set_styling`], that is used.
+/// * If `NO_COLOR` is set and not empty, output is not styled.
+/// * If `CLICOLOR_FORCE` is set and not `0`, output is styled.
+/// * Otherwise, output is styled if the stream is a terminal.
+///
+/// Each stream is checked separately, so `stderr` can be styled while `stdout` is piped into a
+/// file.
+#[cfg(feature = "std")]
+pub fn should_style(stream: Stream) -> bool {
+    use std::{
+        env,
+        io::{IsTerminal, stderr, stdout},
+    };
+
+    if STYLING_OVERRIDDEN.load(Ordering::Relaxed) {
+        return STYLING.load(Ordering::Relaxed);
+    }
+
+    let is_terminal = match stream {
+        Stream::Stdout => stdout().is_terminal(),
+        Stream::Stderr => stderr().is_terminal(),
+    };
+
+    detect_styling(
+        env::var_os("NO_COLOR").as_deref(),
+        env::var_os("CLICOLOR_FORCE").as_deref(),
+        is_terminal,
+    )
+}
+
+/// Returns if output should be styled from the `NO_COLOR` and `CLICOLOR_FORCE` environment
+/// variables, and if the output is a terminal.
+#[cfg(feature = "std")]
+fn detect_styling(
+    no_color: Option<&std::ffi::OsStr>,
+    clicolor_force: Option<&std::ffi::OsStr>,
+    is_terminal: bool,
+) -> bool {
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    if clicolor_force.is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+
+    is_terminal
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    use crate::{
+        StripStyles,
+        style::{BOLD, CLEAR_TERMINAL, ERASE_LINE_UP, RED, RESET},
+    };
+
+    #[test]
+    fn strips_escape_sequences() {
+        let mut output = String::new();
+        let mut writer = StripStyles::new(&mut output);
+        write!(
+            writer,
+            "{BOLD}{RED}error{RESET}: {CLEAR_TERMINAL}plain{ERASE_LINE_UP} text"
+        )
+        .expect("writing to a string should succeed");
+        // Sequences split across writes are still removed.
+        writer
+            .write_str("\x1b[9")
+            .and_then(|()| writer.write_str("1m!"))
+            .expect("writing to a string should succeed");
+
+        assert_eq!("error: plain text!", output);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn detects_styling() {
+        use std::ffi::OsStr;
+
+        use crate::styling::detect_styling;
+
+        assert!(detect_styling(None, None, true));
+        assert!(!detect_styling(None, None, false));
+        assert!(!detect_styling(Some(OsStr::new("1")), None, true));
+        assert!(detect_styling(Some(OsStr::new("")), None, true));
+        assert!(detect_styling(None, Some(OsStr::new("1")), false));
+        assert!(!detect_styling(None, Some(OsStr::new("0")), false));
+        assert!(!detect_styling(
+            Some(OsStr::new("1")),
+            Some(OsStr::new("1")),
+            true
+        ));
+    }
+}
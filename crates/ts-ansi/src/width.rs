@@ -0,0 +1,86 @@
+//! Measure and truncate text by the columns it is displayed in.
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{StripStyles, style::RESET, wrap::is_escape_end};
+
+/// Returns the number of columns some text is displayed in, excluding ANSI escape sequences. Wide
+/// characters, e.g., CJK glyphs, are two columns.
+pub fn visible_width(text: &str) -> usize {
+    let mut visible = String::with_capacity(text.len());
+    // Writing to a string cannot fail.
+    let _ = StripStyles::new(&mut visible).write_str(text);
+
+    visible.width()
+}
+
+/// Truncate text to at most `max` visible columns without breaking ANSI escape sequences.
+///
+/// If the text is cut while styled, [`RESET`] is appended so the styling does not continue past the
+/// truncated text.
+pub fn truncate_visible(text: &str, max: usize) -> String {
+    let mut truncated = String::with_capacity(text.len());
+    let mut width = 0;
+    let mut escape = String::new();
+    let mut is_styled = false;
+
+    for character in text.chars() {
+        if character == '\x1b' || !escape.is_empty() {
+            escape.push(character);
+            if is_escape_end(character) {
+                is_styled = escape != RESET;
+                truncated.push_str(&core::mem::take(&mut escape));
+            }
+            continue;
+        }
+
+        let character_width = character.width().unwrap_or(0);
+        if width + character_width > max {
+            if is_styled {
+                truncated.push_str(RESET);
+            }
+            return truncated;
+        }
+
+        width += character_width;
+        truncated.push(character);
+    }
+
+    truncated.push_str(&escape);
+    truncated
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::format;
+
+    use crate::{
+        style::{BOLD, GREEN, RED, RESET},
+        truncate_visible, visible_width,
+    };
+
+    #[test]
+    fn measures_visible_width() {
+        assert_eq!(0, visible_width(""));
+        assert_eq!(5, visible_width(&format!("{BOLD}{RED}error{RESET}")));
+        assert_eq!(6, visible_width("다람쥐"));
+        assert_eq!(9, visible_width(&format!("{GREEN}ok{RESET} 다람쥐")));
+    }
+
+    #[test]
+    fn truncates_to_visible_width() {
+        let text = format!("{GREEN}success{RESET}: built");
+
+        assert_eq!(text, truncate_visible(&text, 20));
+        assert_eq!(format!("{GREEN}succ{RESET}"), truncate_visible(&text, 4));
+        assert_eq!(
+            format!("{GREEN}success{RESET}: b"),
+            truncate_visible(&text, 10)
+        );
+        assert_eq!("다람", truncate_visible("다람쥐", 5));
+        assert_eq!("", truncate_visible("다람쥐", 1));
+    }
+}
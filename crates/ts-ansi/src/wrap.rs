@@ -0,0 +1,105 @@
+//! Wrap text to a width without breaking ANSI escape sequences.
+
+use alloc::{string::String, vec::Vec};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::visible_width;
+
+/// Wrap text into lines of at most `width` visible columns, breaking on whitespace.
+///
+/// * ANSI escape sequences do not count towards the width and are never broken.
+/// * Wide characters, e.g., CJK glyphs, are two columns.
+/// * Words wider than `width` are broken across lines.
+/// * Runs of whitespace are collapsed into a single space.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for word in text.split_whitespace() {
+        let word_width = visible_width(word);
+
+        if line_width != 0 && line_width + 1 + word_width > width {
+            lines.push(core::mem::take(&mut line));
+            line_width = 0;
+        }
+
+        if line_width != 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+
+        let mut escape = false;
+        for character in word.chars() {
+            if character == '\x1b' {
+                escape = true;
+            }
+            if escape {
+                line.push(character);
+                escape = !is_escape_end(character);
+                continue;
+            }
+
+            let character_width = character.width().unwrap_or(0);
+            if line_width != 0 && line_width + character_width > width {
+                lines.push(core::mem::take(&mut line));
+                line_width = 0;
+            }
+            line.push(character);
+            line_width += character_width;
+        }
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Returns if a character ends an ANSI escape sequence.
+pub(crate) fn is_escape_end(character: char) -> bool {
+    character.is_ascii_alphabetic()
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{format, vec};
+
+    use crate::{
+        style::{BOLD, RED, RESET},
+        wrap,
+    };
+
+    #[test]
+    fn wraps_on_whitespace() {
+        assert_eq!(
+            vec!["the quick", "brown fox", "jumps over", "the lazy", "dog"],
+            wrap("the quick brown fox jumps  over\nthe lazy dog", 10)
+        );
+        assert_eq!(vec!["abcd", "efgh", "ij"], wrap("abcdefghij", 4));
+        assert_eq!(vec![""], wrap("", 4));
+    }
+
+    #[test]
+    fn wraps_wide_characters() {
+        assert_eq!(vec!["다람쥐", "다람쥐"], wrap("다람쥐 다람쥐", 8));
+        assert_eq!(vec!["다람", "쥐"], wrap("다람쥐", 5));
+        assert_eq!(vec!["다", "람"], wrap("다람", 1));
+    }
+
+    #[test]
+    fn preserves_escape_sequences() {
+        let text = format!("{BOLD}{RED}error{RESET}: something failed");
+        assert_eq!(
+            vec![
+                format!("{BOLD}{RED}error{RESET}:"),
+                "something".into(),
+                "failed".into()
+            ],
+            wrap(&text, 10)
+        );
+    }
+}
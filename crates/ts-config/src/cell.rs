@@ -0,0 +1,122 @@
+//! Cache a config file, reloading it when the file changes.
+
+use alloc::sync::Arc;
+use std::{
+    fs,
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use crate::{ConfigFile, LoadConfigError, try_load};
+
+/// A cached config file, that is reloaded when the file's modified time changes.
+#[derive(Debug)]
+pub struct ConfigCell<C> {
+    /// The cached config, if it has been loaded.
+    cache: Mutex<Option<CachedConfig<C>>>,
+}
+
+/// A loaded config and the modified time of the file it was loaded from.
+#[derive(Debug)]
+struct CachedConfig<C> {
+    /// When the config file was modified.
+    modified: SystemTime,
+    /// The loaded config.
+    config: Arc<C>,
+}
+
+impl<C> Default for ConfigCell<C> {
+    fn default() -> Self {
+        Self {
+            cache: Mutex::new(None),
+        }
+    }
+}
+
+impl<C: ConfigFile> ConfigCell<C> {
+    /// Create a new, empty, config cell.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the config, loading it if the config file has changed since it was last loaded.
+    pub fn get(&self) -> Result<Arc<C>, LoadConfigError> {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let modified = fs::metadata(C::config_file_path())
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        if let Some(cached) = cache.as_ref()
+            && modified == Some(cached.modified)
+        {
+            return Ok(Arc::clone(&cached.config));
+        }
+
+        let config = Arc::new(try_load::<C>()?);
+
+        *cache = modified.map(|modified| CachedConfig {
+            modified,
+            config: Arc::clone(&config),
+        });
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::sync::Arc;
+    use core::time::Duration;
+    use std::{
+        env,
+        fs::{self, File},
+        path::PathBuf,
+    };
+
+    use crate::{ConfigCell, ConfigFile};
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Config {
+        field_a: usize,
+    }
+    impl ConfigFile for Config {
+        fn config_file_path() -> PathBuf {
+            env::temp_dir().join("ts-config-config-cell.json")
+        }
+    }
+
+    #[test]
+    fn reloads_on_change() {
+        Config { field_a: 1 }
+            .write()
+            .expect("writing config should succeed");
+
+        let cell = ConfigCell::<Config>::new();
+        let first = cell.get().expect("config should load");
+        let second = cell.get().expect("config should load");
+        assert!(Arc::ptr_eq(&first, &second));
+
+        Config { field_a: 2 }
+            .write()
+            .expect("writing config should succeed");
+        let modified = fs::metadata(Config::config_file_path())
+            .and_then(|metadata| metadata.modified())
+            .expect("modified time should be available");
+        File::options()
+            .write(true)
+            .open(Config::config_file_path())
+            .and_then(|file| file.set_modified(modified + Duration::from_secs(10)))
+            .expect("setting modified time should succeed");
+
+        let third = cell.get().expect("config should load");
+        assert!(!Arc::ptr_eq(&second, &third));
+        assert_eq!(Config { field_a: 2 }, *third);
+
+        Config::default()
+            .delete()
+            .expect("deleting config should succeed");
+    }
+}
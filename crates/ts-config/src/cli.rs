@@ -1,13 +1,23 @@
 //! CLI subcommands for config files
 
-use std::{fs, io::stdin, process};
+use core::{error::Error, str::FromStr};
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    process,
+};
 
 use argh::FromArgs;
 use ts_ansi::format_success;
-use ts_error::ProgramReport;
+use ts_error::{
+    ProgramReport,
+    diagnostic::{Diagnostic, Diagnostics},
+};
 use ts_path::DisplayPath;
+use ts_terminal::confirm;
 
-use crate::{ConfigFile, try_load};
+use crate::{ConfigFile, LoadConfigError, Loaded, example_config, load_report};
 
 #[derive(FromArgs, Debug, PartialEq)]
 #[argh(
@@ -78,26 +88,77 @@ pub enum ConfigSubcommand {
 #[argh(subcommand, name = "lint")]
 #[non_exhaustive]
 /// Lint the config file.
-pub struct LintSubcommand {}
+pub struct LintSubcommand {
+    #[argh(option, default = "LintFormat::Human")]
+    /// the output format, either `human` or `json`
+    format: LintFormat,
+
+    #[argh(switch)]
+    /// treat warnings as errors
+    deny_warnings: bool,
+}
 impl LintSubcommand {
-    /// Lints the config, exits the application on success, or failure.
+    /// Lints the config, exits the application on success, or failure. Warnings only fail the lint
+    /// if `--deny-warnings` is set.
     pub fn execute<C: ConfigFile>(&self) -> ! {
-        let exit_code = match try_load::<C>() {
-            Ok(_) => {
-                eprintln!("{}", format_success!("config file is valid"));
-                0
-            }
-            Err(error) => {
-                let report = ProgramReport::from(error);
-                eprintln!("{report}");
-                1
-            }
-        };
+        let result = load_report::<C>();
+        let exit_code = lint_exit_code(&result, self.deny_warnings);
+
+        match self.format {
+            LintFormat::Human => match result {
+                Ok(loaded) => {
+                    if loaded.diagnostics.has_warnings() {
+                        eprint!("{}", loaded.diagnostics);
+                    }
+                    if exit_code == 0 {
+                        eprintln!("{}", format_success!("config file is valid"));
+                    }
+                }
+                Err(LoadConfigError::ReadConfig { source, .. }) => {
+                    eprintln!("{}", source.as_diagnostic());
+                }
+                Err(error) => {
+                    let report = ProgramReport::from(error);
+                    eprintln!("{report}");
+                }
+            },
+            LintFormat::Json => match lint_json(&result) {
+                Ok(json) => println!("{json}"),
+                Err(error) => {
+                    let report = ProgramReport::from(error);
+                    eprintln!("{report}");
+                    process::exit(1);
+                }
+            },
+        }
 
         process::exit(exit_code)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+/// The output format for linting.
+pub enum LintFormat {
+    /// Human readable diagnostics on `stderr`.
+    Human,
+    /// The diagnostics as a JSON object on `stdout`, see [`Diagnostics::to_json`].
+    Json,
+}
+impl FromStr for LintFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown format `{value}`, expected `human` or `json`"
+            )),
+        }
+    }
+}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "init")]
 #[non_exhaustive]
@@ -106,26 +167,35 @@ pub struct InitSubcommand {
     #[argh(switch)]
     /// forcefully overwrite any existing config file
     force: bool,
+
+    #[argh(switch)]
+    /// write a documented example config, with the description of each setting as a comment
+    example: bool,
 }
 impl InitSubcommand {
     /// Initialise the config, exits the application on success, or failure.
     pub fn execute<C: ConfigFile>(&self) -> ! {
+        if let Err(error) = check_writable(&C::config_file_path()) {
+            let report = ProgramReport::from(error);
+            eprintln!("{report}");
+            process::exit(1);
+        }
+
         match fs::exists(C::config_file_path()) {
             Ok(exists) => {
                 if exists && !self.force {
-                    eprint!(
-                        "A config file already exists at ({}), overwrite it (y/n): ",
+                    let prompt = format!(
+                        "A config file already exists at ({}), overwrite it",
                         C::config_file_path().opinionated_display()
                     );
-                    let mut buffer = String::new();
-                    if let Err(error) = stdin().read_line(&mut buffer) {
-                        let report = ProgramReport::from(error);
-                        eprintln!("{report}");
-                        process::exit(1);
-                    };
-
-                    if buffer.trim_end() != "y" {
-                        process::exit(1);
+                    match confirm(&prompt, false) {
+                        Ok(true) => {}
+                        Ok(false) => process::exit(1),
+                        Err(error) => {
+                            let report = ProgramReport::from(error);
+                            eprintln!("{report}");
+                            process::exit(1);
+                        }
                     }
                 }
             }
@@ -136,7 +206,12 @@ impl InitSubcommand {
             }
         }
 
-        if let Err(error) = C::default().write() {
+        let result = if self.example {
+            fs::write(C::config_file_path(), example_config::<C>())
+        } else {
+            C::default().write()
+        };
+        if let Err(error) = result {
             let report = ProgramReport::from(error);
             eprintln!("{report}");
             process::exit(1)
@@ -145,10 +220,249 @@ impl InitSubcommand {
         eprintln!(
             "{}",
             format_success!(
-                "initialised default config at {}",
+                "initialised {} config at {}",
+                if self.example { "example" } else { "default" },
                 C::config_file_path().opinionated_display()
             )
         );
         process::exit(0)
     }
 }
+
+/// Error variants for checking if a config file can be written.
+#[derive(Debug)]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub enum CheckWritableError {
+    #[non_exhaustive]
+    PermissionDenied { path: PathBuf },
+
+    #[non_exhaustive]
+    CheckAccess { path: PathBuf, source: io::Error },
+}
+impl core::fmt::Display for CheckWritableError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self {
+            Self::PermissionDenied { path, .. } => write!(
+                f,
+                "cannot write to `{}`: permission denied",
+                path.opinionated_display()
+            ),
+            Self::CheckAccess { path, .. } => write!(
+                f,
+                "could not check if `{}` can be written to",
+                path.opinionated_display()
+            ),
+        }
+    }
+}
+impl Error for CheckWritableError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self {
+            Self::CheckAccess { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Check the directory containing `path` can be written to, by creating and removing a probe file.
+fn check_writable(path: &Path) -> Result<(), CheckWritableError> {
+    let directory = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    let mut probe_name = path.file_name().unwrap_or_default().to_os_string();
+    probe_name.push(".write-check");
+    let probe = directory.join(probe_name);
+
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => fs::remove_file(&probe).map_err(|source| CheckWritableError::CheckAccess {
+            path: directory.to_path_buf(),
+            source,
+        }),
+        Err(source) if source.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+        Err(source) if source.kind() == io::ErrorKind::PermissionDenied => {
+            Err(CheckWritableError::PermissionDenied {
+                path: directory.to_path_buf(),
+            })
+        }
+        Err(source) => Err(CheckWritableError::CheckAccess {
+            path: directory.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+/// Returns the exit code for the result of linting a config file, a config with only warnings
+/// fails if `deny_warnings` is set.
+fn lint_exit_code<C>(result: &Result<Loaded<C>, LoadConfigError>, deny_warnings: bool) -> i32 {
+    match result {
+        Ok(loaded) => i32::from(deny_warnings && loaded.diagnostics.has_warnings()),
+        Err(_) => 1,
+    }
+}
+
+/// Serialize the result of linting a config file as a JSON array of diagnostics.
+///
+/// A config file that could not be read is reported as its read error diagnostic, see
+/// [`ReadFileError::as_diagnostic`](ts_io::ReadFileError::as_diagnostic). Other errors that are not
+/// diagnostics are converted into a single error diagnostic.
+fn lint_json<C>(result: &Result<Loaded<C>, LoadConfigError>) -> Result<String, serde_json::Error> {
+    let error = match result {
+        Ok(loaded) => return loaded.diagnostics.to_json(),
+        Err(LoadConfigError::InvalidConfig { source, .. }) => return source.to_json(),
+        Err(error) => error,
+    };
+
+    let diagnostic = if let LoadConfigError::ReadConfig { source, .. } = error {
+        source.as_diagnostic()
+    } else {
+        let mut diagnostic = Diagnostic::error(error);
+        let mut current_error = error.source();
+        while let Some(error) = current_error {
+            diagnostic.notes.push(error.to_string());
+            current_error = error.source();
+        }
+        diagnostic
+    };
+
+    let mut diagnostics = Diagnostics::new("loading config file");
+    diagnostics.push(diagnostic);
+    diagnostics.to_json()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use serde_json::Value;
+    use ts_error::diagnostic::{Diagnostic, Diagnostics};
+    use ts_path::DisplayPath;
+
+    use crate::{
+        ConfigFile, LoadConfigError, Loaded,
+        cli::{CheckWritableError, check_writable, lint_exit_code, lint_json},
+        load_report,
+    };
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Config {
+        field_a: usize,
+    }
+    impl ConfigFile for Config {
+        fn config_file_path() -> PathBuf {
+            env::temp_dir().join("ts-config-lint-json.json")
+        }
+    }
+
+    #[test]
+    fn lints_as_json() {
+        fs::write(Config::config_file_path(), r#"{ "field_a": "five" }"#)
+            .expect("writing config should succeed");
+        let json = lint_json(&load_report::<Config>()).expect("serialization should succeed");
+        let value: Value = serde_json::from_str(&json).expect("output should be valid JSON");
+        let problems = value
+            .get("problems")
+            .and_then(Value::as_array)
+            .expect("output should have problems");
+        assert_eq!(1, problems.len());
+        assert_eq!(
+            Some("Error"),
+            problems
+                .first()
+                .and_then(|problem| problem.get("severity"))
+                .and_then(Value::as_str)
+        );
+
+        Config::default()
+            .write()
+            .expect("writing config should succeed");
+        let json = lint_json(&load_report::<Config>()).expect("serialization should succeed");
+        let value: Value = serde_json::from_str(&json).expect("output should be valid JSON");
+        assert_eq!(
+            Some(0),
+            value
+                .get("problems")
+                .and_then(Value::as_array)
+                .map(Vec::len)
+        );
+
+        Config::default()
+            .delete()
+            .expect("deleting config should succeed");
+
+        let json = lint_json(&load_report::<Config>()).expect("serialization should succeed");
+        let value: Value = serde_json::from_str(&json).expect("output should be valid JSON");
+        let problem = value
+            .get("problems")
+            .and_then(Value::as_array)
+            .and_then(|problems| problems.first())
+            .expect("output should have a problem");
+        assert_eq!(
+            Some(Config::config_file_path().opinionated_display().to_string()),
+            problem
+                .get("file_path")
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+        );
+    }
+
+    #[test]
+    fn exits_on_warnings_when_denied() {
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        diagnostics.push(Diagnostic::warning("some warning"));
+        let warnings: Result<Loaded<Config>, LoadConfigError> = Ok(Loaded {
+            config: Config::default(),
+            diagnostics,
+        });
+        assert_eq!(0, lint_exit_code(&warnings, false));
+        assert_eq!(1, lint_exit_code(&warnings, true));
+
+        let clean: Result<Loaded<Config>, LoadConfigError> = Ok(Loaded {
+            config: Config::default(),
+            diagnostics: Diagnostics::new("validating JSON"),
+        });
+        assert_eq!(0, lint_exit_code(&clean, true));
+
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        diagnostics.push(Diagnostic::error("some error"));
+        let errors: Result<Loaded<Config>, LoadConfigError> = Err(LoadConfigError::InvalidConfig {
+            source: diagnostics,
+        });
+        assert_eq!(1, lint_exit_code(&errors, false));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn rejects_read_only_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = env::temp_dir().join("ts-config-read-only");
+        fs::create_dir_all(&directory).expect("creating directory should succeed");
+        fs::set_permissions(&directory, fs::Permissions::from_mode(0o555))
+            .expect("setting permissions should succeed");
+
+        let path = directory.join("config.json");
+        let result = check_writable(&path);
+
+        // Privileged users can write to read-only directories.
+        let is_privileged = fs::write(directory.join("privileged"), "").is_ok();
+
+        fs::set_permissions(&directory, fs::Permissions::from_mode(0o755))
+            .expect("setting permissions should succeed");
+        fs::remove_dir_all(&directory).expect("removing directory should succeed");
+
+        if is_privileged {
+            assert!(result.is_ok());
+            return;
+        }
+
+        let error = result.expect_err("directory should not be writable");
+        assert!(matches!(error, CheckWritableError::PermissionDenied { .. }));
+        assert!(error.to_string().ends_with("permission denied"));
+        assert!(!fs::exists(&path).unwrap_or(true));
+    }
+}
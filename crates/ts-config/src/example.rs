@@ -0,0 +1,173 @@
+//! Generate a documented example of a config file.
+
+use serde_json::Value;
+
+use crate::{ConfigFile, config_schema};
+
+/// Generate a documented example of a config file as JSON with comments, e.g., as a starting point
+/// for users. Each property is preceded by its description as a comment, and is set to its
+/// `default`, or first of its `examples`, from the config's JSON schema. Otherwise the property is
+/// set to its value in the default config.
+///
+/// Comments are allowed in config files, so the example can be written as the config file and
+/// loaded as is.
+pub fn example_config<C: ConfigFile>() -> String {
+    let schema = config_schema::<C>();
+    let root = schema.as_value();
+    let default = serde_json::to_value(C::default()).unwrap_or(Value::Null);
+
+    let mut output = String::new();
+    if let Some(description) = root.get("description").and_then(Value::as_str) {
+        write_comment(&mut output, description, "");
+    }
+    write_value(&mut output, root, root, &default, C::indent(), 0);
+    output.push('\n');
+
+    output
+}
+
+/// Write the example value for a schema, objects with properties are written with a documented
+/// example for each property, other schemas are written as their default value.
+fn write_value(
+    output: &mut String,
+    root: &Value,
+    schema: &Value,
+    default: &Value,
+    indent: &str,
+    depth: usize,
+) {
+    let Some(properties) = resolve(root, schema)
+        .get("properties")
+        .and_then(Value::as_object)
+        .filter(|properties| !properties.is_empty())
+    else {
+        output.push_str(&default.to_string());
+        return;
+    };
+
+    let inner_indent = indent.repeat(depth + 1);
+    output.push_str("{\n");
+
+    for (index, (key, property)) in properties.iter().enumerate() {
+        if let Some(description) = annotation(root, property, "description").and_then(Value::as_str)
+        {
+            write_comment(output, description, &inner_indent);
+        }
+        output.push_str(&format!("{inner_indent}{}: ", Value::from(key.as_str())));
+
+        let example = annotation(root, property, "default").or_else(|| {
+            annotation(root, property, "examples")
+                .and_then(Value::as_array)
+                .and_then(|examples| examples.first())
+        });
+        let value = example.or_else(|| default.get(key)).unwrap_or(&Value::Null);
+        write_value(output, root, property, value, indent, depth + 1);
+
+        if index + 1 < properties.len() {
+            output.push(',');
+        }
+        output.push('\n');
+    }
+
+    output.push_str(&indent.repeat(depth));
+    output.push('}');
+}
+
+/// Write a description as line comments.
+fn write_comment(output: &mut String, description: &str, indent: &str) {
+    for line in description.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            output.push_str(&format!("{indent}//\n"));
+        } else {
+            output.push_str(&format!("{indent}// {line}\n"));
+        }
+    }
+}
+
+/// Returns an annotation of a schema, or of the schema it refers to.
+fn annotation<'s>(root: &'s Value, schema: &'s Value, key: &str) -> Option<&'s Value> {
+    schema.get(key).or_else(|| resolve(root, schema).get(key))
+}
+
+/// Follow `$ref`s, and `allOf`s of a single schema, to the schema they refer to.
+fn resolve<'s>(root: &'s Value, schema: &'s Value) -> &'s Value {
+    if let Some(target) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+    {
+        return resolve(root, target);
+    }
+
+    if let Some([inner]) = schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        return resolve(root, inner);
+    }
+
+    schema
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::{ConfigFile, example_config, try_load};
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    /// Where to listen.
+    struct Address {
+        /// The host name.
+        host: String,
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    #[serde(default)]
+    /// The server config.
+    ///
+    /// Restart the server after changing it.
+    struct Config {
+        /// The port to listen on.
+        port: u16,
+        /// Where to listen.
+        address: Address,
+        retries: Option<u8>,
+    }
+    impl Default for Config {
+        fn default() -> Self {
+            Self {
+                port: 8080,
+                address: Address::default(),
+                retries: None,
+            }
+        }
+    }
+    impl ConfigFile for Config {
+        fn config_file_path() -> PathBuf {
+            env::temp_dir().join("ts-config-example.json")
+        }
+    }
+
+    #[test]
+    fn generates_documented_example() {
+        let example = example_config::<Config>();
+        assert_eq!(
+            "// The server config.\n//\n// Restart the server after changing it.\n{\n  // Where to listen.\n  \"address\": {\n    // The host name.\n    \"host\": \"\"\n  },\n  // The port to listen on.\n  \"port\": 8080,\n  \"retries\": null\n}\n",
+            example
+        );
+
+        // The example loads as the default config.
+        fs::write(Config::config_file_path(), &example).expect("writing example should succeed");
+        let config = try_load::<Config>();
+        Config::default()
+            .delete()
+            .expect("deleting config should succeed");
+        assert_eq!(Config::default(), config.expect("example should load"));
+    }
+}
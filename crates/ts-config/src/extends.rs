@@ -0,0 +1,354 @@
+//! Resolve config files that extend other config files.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+use ts_io::{ReadFileError, read_file_to_string};
+use ts_json::strip_comments;
+
+/// Error variants for resolving the config files that a config file extends.
+#[derive(Debug)]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub enum ExtendsError {
+    #[non_exhaustive]
+    Cycle { path: PathBuf },
+
+    #[non_exhaustive]
+    InvalidExtends { path: PathBuf },
+
+    #[non_exhaustive]
+    ParseFile {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[non_exhaustive]
+    ReadFile { source: ReadFileError },
+}
+impl core::fmt::Display for ExtendsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self {
+            Self::Cycle { path } => write!(
+                f,
+                "config file `{}` extends itself through a cycle",
+                path.display()
+            ),
+            Self::InvalidExtends { path } => write!(
+                f,
+                "`extends` in config file `{}` must be a path or an array of paths",
+                path.display()
+            ),
+            Self::ParseFile { path, .. } => {
+                write!(f, "config file `{}` is not valid JSON", path.display())
+            }
+            Self::ReadFile { .. } => write!(f, "could not read extended config file"),
+        }
+    }
+}
+impl core::error::Error for ExtendsError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self {
+            Self::ParseFile { source, .. } => Some(source),
+            Self::ReadFile { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A config merged over the config files it extends.
+#[derive(Debug)]
+pub(crate) struct Merged {
+    /// The index into `sources` of the config file that last defined the value at each JSON
+    /// pointer.
+    pub(crate) origins: HashMap<String, usize>,
+    /// The path and source of each config file that was merged.
+    pub(crate) sources: Vec<(PathBuf, String)>,
+    /// The merged config.
+    pub(crate) value: Value,
+}
+impl Merged {
+    /// Returns the index into `sources` of the config file that last defined the value at a JSON
+    /// pointer, or its closest defined parent.
+    pub(crate) fn origin(&self, pointer: &str) -> Option<usize> {
+        let mut pointer = pointer;
+        loop {
+            if let Some(origin) = self.origins.get(pointer) {
+                return Some(*origin);
+            }
+            pointer = pointer.get(..pointer.rfind('/')?)?;
+        }
+    }
+}
+
+/// Resolve the top-level `extends` property of a config file, returning the config merged over the
+/// config files it extends.
+///
+/// Returns `None` if the config file does not extend any files, or is not valid JSON, so the source
+/// can be linted as is.
+pub(crate) fn resolve_extends(path: &Path, source: &str) -> Result<Option<Merged>, ExtendsError> {
+    let Ok(value) = serde_json::from_str::<Value>(&strip_comments(source)) else {
+        return Ok(None);
+    };
+
+    if value.get("extends").is_none() {
+        return Ok(None);
+    }
+
+    let mut origins = HashMap::new();
+    let mut sources = vec![(path.to_path_buf(), source.to_string())];
+    let mut stack = Vec::new();
+    let value = resolve(path, value, 0, &mut origins, &mut sources, &mut stack)?;
+
+    Ok(Some(Merged {
+        origins,
+        sources,
+        value,
+    }))
+}
+
+/// Resolve the config files that a config file extends, `origin` is the index of the config file
+/// in `sources`, and `stack` is the chain of config files currently being resolved. The path and
+/// source of each config file that is read are pushed to `sources`, see [`Merged`].
+fn resolve(
+    path: &Path,
+    mut value: Value,
+    origin: usize,
+    origins: &mut HashMap<String, usize>,
+    sources: &mut Vec<(PathBuf, String)>,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Value, ExtendsError> {
+    let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&canonical_path) {
+        return Err(ExtendsError::Cycle {
+            path: path.to_path_buf(),
+        });
+    }
+    stack.push(canonical_path);
+
+    let extends = match value
+        .as_object_mut()
+        .and_then(|object| object.remove("extends"))
+    {
+        None => Vec::new(),
+        Some(Value::String(extends)) => vec![extends],
+        Some(Value::Array(extends)) => extends
+            .into_iter()
+            .map(|extends| match extends {
+                Value::String(extends) => Ok(extends),
+                _ => Err(ExtendsError::InvalidExtends {
+                    path: path.to_path_buf(),
+                }),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(ExtendsError::InvalidExtends {
+                path: path.to_path_buf(),
+            });
+        }
+    };
+
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut resolved = Value::Object(Map::new());
+    for extends in extends {
+        let base_path = directory.join(extends);
+        let base_source =
+            read_file_to_string(&base_path).map_err(|source| ExtendsError::ReadFile { source })?;
+        let base = serde_json::from_str(&strip_comments(&base_source)).map_err(|source| {
+            ExtendsError::ParseFile {
+                path: base_path.clone(),
+                source,
+            }
+        })?;
+
+        let base_origin = sources.len();
+        sources.push((base_path.clone(), base_source));
+        merge(
+            &mut resolved,
+            resolve(&base_path, base, base_origin, origins, sources, stack)?,
+        );
+    }
+    // The bases are resolved first, so a config file is recorded as the origin of the values it
+    // redefines.
+    record_origins(origins, String::new(), &value, origin);
+    merge(&mut resolved, value);
+
+    stack.pop();
+    Ok(resolved)
+}
+
+/// Record a config file as the origin of a value and everything it contains, keyed by JSON pointer.
+fn record_origins(
+    origins: &mut HashMap<String, usize>,
+    pointer: String,
+    value: &Value,
+    origin: usize,
+) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let key = key.replace('~', "~0").replace('/', "~1");
+                record_origins(origins, format!("{pointer}/{key}"), value, origin);
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                record_origins(origins, format!("{pointer}/{index}"), value, origin);
+            }
+        }
+        _ => {}
+    }
+    origins.insert(pointer, origin);
+}
+
+/// Deep merge `value` over `target`, objects are merged, all other values are replaced.
+fn merge(target: &mut Value, value: Value) {
+    match (target, value) {
+        (Value::Object(target), Value::Object(value)) => {
+            for (key, value) in value {
+                match target.get_mut(&key) {
+                    Some(target) => merge(target, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::{ConfigFile, LoadConfigError, extends::ExtendsError, try_load};
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Nested {
+        x: usize,
+        y: usize,
+    }
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Config {
+        a: usize,
+        b: usize,
+        nested: Nested,
+    }
+    impl ConfigFile for Config {
+        fn config_file_path() -> PathBuf {
+            env::temp_dir().join("ts-config-extends/config.json")
+        }
+
+        fn resolve_extends() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn merges_extended_config() {
+        let directory = env::temp_dir().join("ts-config-extends");
+        fs::create_dir_all(directory.join("base")).expect("creating directory should succeed");
+
+        fs::write(
+            directory.join("base/base.json"),
+            r#"{ "extends": "./root.json", "a": 1, "nested": { "x": 1, "y": 2 } }"#,
+        )
+        .expect("writing config should succeed");
+        fs::write(directory.join("base/root.json"), r#"{ "a": 0, "b": 0 }"#)
+            .expect("writing config should succeed");
+        fs::write(
+            Config::config_file_path(),
+            r#"{ "extends": ["./base/base.json"], "b": 2, "nested": { "y": 3 } }"#,
+        )
+        .expect("writing config should succeed");
+
+        let config = try_load::<Config>();
+
+        fs::write(
+            directory.join("base/base.json"),
+            "{\n  \"extends\": \"./root.json\",\n  \"nested\": { \"x\": \"one\", \"y\": 2 }\n}",
+        )
+        .expect("writing config should succeed");
+        fs::write(
+            Config::config_file_path(),
+            "{\n  // Override the base.\n  \"extends\": [\"./base/base.json\"],\n  \"b\": \"two\"\n}",
+        )
+        .expect("writing config should succeed");
+        let invalid = try_load::<Config>();
+
+        // a -> b -> a
+        fs::write(
+            directory.join("base/base.json"),
+            r#"{ "extends": "../config.json" }"#,
+        )
+        .expect("writing config should succeed");
+        let cycle = try_load::<Config>();
+
+        fs::remove_dir_all(&directory).expect("removing directory should succeed");
+
+        assert_eq!(
+            Config {
+                a: 1,
+                b: 2,
+                nested: Nested { x: 1, y: 3 }
+            },
+            config.expect("config should load")
+        );
+
+        let Err(LoadConfigError::InvalidConfig {
+            source: diagnostics,
+        }) = invalid
+        else {
+            panic!("merged config should be invalid");
+        };
+        let locations: Vec<_> = diagnostics
+            .problems
+            .iter()
+            .map(|problem| {
+                (
+                    problem.headline.as_str(),
+                    problem.file_path.clone().unwrap_or_default(),
+                    problem.context.as_ref().map(|context| context.span.line),
+                )
+            })
+            .collect();
+        assert_eq!(2, locations.len(), "{locations:?}");
+        assert!(locations.contains(&(
+            "`/b` is the wrong type",
+            Config::config_file_path().display().to_string(),
+            Some(4)
+        )));
+        assert!(locations.iter().any(|(headline, file_path, line)| {
+            headline.starts_with("`/nested/x`")
+                && file_path.ends_with("base.json")
+                && *line == Some(3)
+        }));
+
+        let error = cycle.expect_err("cycle should not load");
+        assert!(matches!(
+            &error,
+            LoadConfigError::ResolveExtends {
+                source: ExtendsError::Cycle { .. }
+            }
+        ));
+        assert_eq!(
+            format!(
+                "config file `{}` extends itself through a cycle",
+                directory.join("./base/../config.json").display()
+            ),
+            core::error::Error::source(&error)
+                .map(ToString::to_string)
+                .unwrap_or_default()
+        );
+    }
+}
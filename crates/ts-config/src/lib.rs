@@ -2,17 +2,30 @@
 //!
 //! Helpers for application config.
 
+extern crate alloc;
+
+mod cell;
 #[cfg(feature = "cli")]
 pub mod cli;
+mod example;
+mod extends;
 mod load;
+mod status;
 
 use std::{fs, io, path::PathBuf};
 
 use schemars::JsonSchema;
 use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Serializer, Value, ser::PrettyFormatter};
 
-pub use load::{LoadConfigError, try_load};
+pub use cell::ConfigCell;
+pub use example::example_config;
+pub use extends::ExtendsError;
+pub use load::{
+    LoadConfigError, Loaded, config_schema, lint_against_schema, load_report, try_load, try_load_in,
+};
 pub use schemars;
+pub use status::{ConfigStatus, status};
 
 /// Trait defining a struct as representing a config file.
 pub trait ConfigFile: Default + DeserializeOwned + Serialize + JsonSchema {
@@ -24,9 +37,119 @@ pub trait ConfigFile: Default + DeserializeOwned + Serialize + JsonSchema {
         fs::remove_file(Self::config_file_path())
     }
 
+    /// The indent used when writing the config file.
+    fn indent() -> &'static str {
+        "  "
+    }
+
+    /// The `$id` of the config's generated JSON schema, so tooling and `$ref` resolvers can
+    /// identify the schema.
+    fn json_schema_id() -> Option<&'static str> {
+        None
+    }
+
+    /// If the config file may extend other config files using a top-level `extends` property of a
+    /// path, or an array of paths, relative to the config file. The config file is deep merged
+    /// over the files it extends before it is linted, see [`try_load`] for where its diagnostics are
+    /// located.
+    fn resolve_extends() -> bool {
+        false
+    }
+
+    /// The generated JSON schema for the config as a JSON value, see [`config_schema`].
+    fn schema_value() -> Result<Value, serde_json::Error> {
+        Ok(config_schema::<Self>().to_value())
+    }
+
+    /// If the keys of objects should be sorted alphabetically when writing the config file,
+    /// otherwise keys are written in the order they are serialized.
+    fn sort_keys() -> bool {
+        false
+    }
+
     /// Write the config file.
     fn write(&self) -> io::Result<()> {
-        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let mut json = Vec::new();
+        let formatter = PrettyFormatter::with_indent(Self::indent().as_bytes());
+        let mut serializer = Serializer::with_formatter(&mut json, formatter);
+
+        if Self::sort_keys() {
+            let mut value = serde_json::to_value(self).map_err(io::Error::other)?;
+            value.sort_all_objects();
+            value.serialize(&mut serializer)
+        } else {
+            self.serialize(&mut serializer)
+        }
+        .map_err(io::Error::other)?;
+
         fs::write(Self::config_file_path(), json)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::ConfigFile;
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Nested {
+        b: usize,
+        a: usize,
+    }
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Config {
+        field_b: usize,
+        field_a: Nested,
+    }
+    impl ConfigFile for Config {
+        fn config_file_path() -> PathBuf {
+            env::temp_dir().join("ts-config-write-formatting.json")
+        }
+
+        fn indent() -> &'static str {
+            "    "
+        }
+
+        fn sort_keys() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn generates_schema_value() {
+        let schema = Config::schema_value().expect("schema should serialize");
+
+        assert_eq!(
+            Some("integer"),
+            schema
+                .pointer("/properties/field_b/type")
+                .and_then(|kind| kind.as_str())
+        );
+        assert!(schema.pointer("/properties/field_a").is_some());
+        assert!(schema.pointer("/definitions/Nested/properties/a").is_some());
+        assert!(schema.pointer("/definitions/Nested/properties/b").is_some());
+    }
+
+    #[test]
+    fn writes_with_formatting() {
+        Config::default()
+            .write()
+            .expect("writing config should succeed");
+        let contents =
+            fs::read_to_string(Config::config_file_path()).expect("reading config should succeed");
+        Config::default()
+            .delete()
+            .expect("deleting config should succeed");
+
+        assert_eq!(
+            "{\n    \"field_a\": {\n        \"a\": 0,\n        \"b\": 0\n    },\n    \"field_b\": 0\n}",
+            contents
+        );
+    }
+}
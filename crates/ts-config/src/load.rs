@@ -1,11 +1,17 @@
 //! Load a config file
 
-use schemars::{SchemaGenerator, generate::SchemaSettings};
-use ts_error::diagnostic::Diagnostics;
+use std::path::{Path, PathBuf};
+
+use schemars::{Schema, SchemaGenerator, generate::SchemaSettings};
+use serde::de::DeserializeOwned;
+use ts_error::diagnostic::{Context, Diagnostics, SourceIndex};
 use ts_io::{ReadFileError, read_file_to_string};
-use ts_json::{ValidationError, validate};
+use ts_json::{ValidationError, Validator, strip_comments, validate};
 
-use crate::ConfigFile;
+use crate::{
+    ConfigFile, ExtendsError,
+    extends::{Merged, resolve_extends},
+};
 
 /// Error variants for loading config.
 #[derive(Debug)]
@@ -26,6 +32,12 @@ pub enum LoadConfigError {
 
     #[non_exhaustive]
     ReadConfig { source: ReadFileError },
+
+    #[non_exhaustive]
+    ReadSchema { source: ReadFileError },
+
+    #[non_exhaustive]
+    ResolveExtends { source: ExtendsError },
 }
 impl core::fmt::Display for LoadConfigError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -37,6 +49,10 @@ impl core::fmt::Display for LoadConfigError {
             Self::InvalidConfig { .. } => write!(f, "config file is invalid"),
             Self::DeserializeConfig { .. } => write!(f, "config file could not be deserialized"),
             Self::ReadConfig { .. } => write!(f, "could not read config file"),
+            Self::ReadSchema { .. } => write!(f, "could not read JSON schema file"),
+            Self::ResolveExtends { .. } => {
+                write!(f, "could not resolve the config files the config extends")
+            }
         }
     }
 }
@@ -48,30 +64,381 @@ impl core::error::Error for LoadConfigError {
             }
             Self::ValidationFailure { source, .. } => Some(source),
             Self::InvalidConfig { source, .. } => Some(source),
-            Self::ReadConfig { source, .. } => Some(source),
+            Self::ReadConfig { source, .. } | Self::ReadSchema { source, .. } => Some(source),
+            Self::ResolveExtends { source, .. } => Some(source),
+        }
+    }
+}
+
+/// A loaded config file, alongside the diagnostics that did not prevent it from loading.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Loaded<C> {
+    /// The config.
+    pub config: C,
+    /// The non-error diagnostics from linting the config file.
+    pub diagnostics: Diagnostics,
+}
+
+/// The source of a config file, see [`read_source`].
+pub(crate) struct ConfigSource {
+    /// The config merged over the config files it extends, if it extends any.
+    pub(crate) merged: Option<Merged>,
+    /// The path of the config file.
+    pub(crate) path: PathBuf,
+    /// The source of the config file, or the merged config formatted as JSON if it extends any
+    /// config files.
+    pub(crate) source: String,
+}
+impl ConfigSource {
+    /// Lint the config against its JSON schema, a merged config is linted as described in
+    /// [`try_load`].
+    pub(crate) fn lint<C: ConfigFile>(&self) -> Result<Diagnostics, LoadConfigError> {
+        match &self.merged {
+            Some(merged) => Ok(validator::<C>()?.validate_merged(
+                &merged.value,
+                &merged.sources,
+                |pointer| merged.origin(pointer),
+            )),
+            None => lint::<C>(&self.source, Some(&self.path)),
         }
     }
 }
 
 /// Try load a config file, linting it against its JSON schema.
+///
+/// The config file may contain `//` and `/* */` comments, such as those in an
+/// [`example_config`](crate::example_config).
+///
+/// If the config file extends other config files, see [`ConfigFile::resolve_extends`], the config
+/// merged over the files it extends is linted rather than each file. Each diagnostic is located in
+/// the config file that last defined the value it is about.
 pub fn try_load<C: ConfigFile>() -> Result<C, LoadConfigError> {
-    let source = read_file_to_string(&C::config_file_path())
-        .map_err(|source| LoadConfigError::ReadConfig { source })?;
+    try_load_from::<C>(C::config_file_path())
+}
 
-    let schema_generator = SchemaGenerator::from(SchemaSettings::draft07());
-    let schema = schema_generator.into_root_schema_for::<C>();
-    let schema = serde_json::to_string(&schema)
-        .map_err(|source| LoadConfigError::SerailizeSchema { source })?;
+/// Try load a config file, linting it against its JSON schema, where a relative
+/// [`ConfigFile::config_file_path`] is resolved against `base` rather than the current directory.
+///
+/// Diagnostics are reported against the resolved path.
+pub fn try_load_in<C: ConfigFile>(base: &Path) -> Result<C, LoadConfigError> {
+    try_load_from::<C>(base.join(C::config_file_path()))
+}
 
-    let diagnostics = validate(&source, &schema, Some(C::config_file_path()).as_deref())
-        .map_err(|source| LoadConfigError::ValidationFailure { source })?;
+/// Try load the config file at a path, linting it against its JSON schema.
+fn try_load_from<C: ConfigFile>(path: PathBuf) -> Result<C, LoadConfigError> {
+    let config_source = read_source_at::<C>(path)?;
+
+    let diagnostics = config_source.lint::<C>()?;
 
     if !diagnostics.is_empty() {
         Err(LoadConfigError::InvalidConfig {
             source: diagnostics,
         })
     } else {
-        serde_json::from_str(&source)
+        serde_json::from_str(&strip_comments(&config_source.source))
             .map_err(|source| LoadConfigError::DeserializeConfig { source })
     }
 }
+
+/// Try load a config file, linting it against its JSON schema. The config is returned alongside
+/// any diagnostics if there are no errors.
+///
+/// Diagnostics for a config file that extends other config files are located as described in
+/// [`try_load`].
+pub fn load_report<C: ConfigFile>() -> Result<Loaded<C>, LoadConfigError> {
+    let config_source = read_source::<C>()?;
+
+    let diagnostics = config_source.lint::<C>()?;
+
+    into_loaded(&config_source.source, diagnostics)
+}
+
+/// Lint a config file against a JSON schema file, rather than the schema of a [`ConfigFile`].
+pub fn lint_against_schema(
+    config_path: &Path,
+    schema_path: &Path,
+) -> Result<Diagnostics, LoadConfigError> {
+    let source = read_file_to_string(config_path)
+        .map_err(|source| LoadConfigError::ReadConfig { source })?;
+    let schema = read_file_to_string(schema_path)
+        .map_err(|source| LoadConfigError::ReadSchema { source })?;
+
+    validate(&source, &schema, Some(config_path))
+        .map_err(|source| LoadConfigError::ValidationFailure { source })
+}
+
+/// Read the source of a config file, merging it over any config files it extends.
+pub(crate) fn read_source<C: ConfigFile>() -> Result<ConfigSource, LoadConfigError> {
+    read_source_at::<C>(C::config_file_path())
+}
+
+/// Read the source of the config file at a path, merging it over any config files it extends.
+fn read_source_at<C: ConfigFile>(path: PathBuf) -> Result<ConfigSource, LoadConfigError> {
+    let source =
+        read_file_to_string(&path).map_err(|source| LoadConfigError::ReadConfig { source })?;
+
+    if C::resolve_extends()
+        && let Some(merged) = resolve_extends(&path, &source)
+            .map_err(|source| LoadConfigError::ResolveExtends { source })?
+    {
+        return Ok(ConfigSource {
+            source: format!("{:#}", merged.value),
+            merged: Some(merged),
+            path,
+        });
+    }
+
+    Ok(ConfigSource {
+        merged: None,
+        path,
+        source,
+    })
+}
+
+/// Generate the JSON schema for a config file, with the `$id` from
+/// [`ConfigFile::json_schema_id`] if it is set.
+pub fn config_schema<C: ConfigFile>() -> Schema {
+    let schema_generator = SchemaGenerator::from(SchemaSettings::draft07());
+    let mut schema = schema_generator.into_root_schema_for::<C>();
+
+    if let Some(id) = C::json_schema_id() {
+        schema.insert("$id".to_string(), id.into());
+    }
+
+    schema
+}
+
+/// Lint the source of a config file against its JSON schema.
+///
+/// Comments are stripped from the source before it is validated, the contexts of the diagnostics
+/// are then taken from the source so they still show the comments.
+pub(crate) fn lint<C: ConfigFile>(
+    source: &str,
+    source_path: Option<&Path>,
+) -> Result<Diagnostics, LoadConfigError> {
+    let mut diagnostics = validator::<C>()?
+        .validate(&strip_comments(source), source_path)
+        .map_err(|source| LoadConfigError::ValidationFailure { source })?;
+
+    let index = SourceIndex::new(source);
+    for problem in &mut diagnostics.problems {
+        for context in problem.context.iter_mut().chain(&mut problem.related) {
+            let mut original = Context::from_index(&index, context.span);
+            original.label = context.label.take();
+            *context = original;
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+/// Compile the JSON schema of a config file into a validator.
+fn validator<C: ConfigFile>() -> Result<Validator, LoadConfigError> {
+    let schema = C::schema_value().map_err(|source| LoadConfigError::SerailizeSchema { source })?;
+
+    Validator::from_value(schema).map_err(|source| LoadConfigError::ValidationFailure { source })
+}
+
+/// Deserialize the source of a config file if the diagnostics contain no errors.
+fn into_loaded<C: DeserializeOwned>(
+    source: &str,
+    diagnostics: Diagnostics,
+) -> Result<Loaded<C>, LoadConfigError> {
+    if diagnostics.has_errors() {
+        return Err(LoadConfigError::InvalidConfig {
+            source: diagnostics,
+        });
+    }
+
+    let config = serde_json::from_str(&strip_comments(source))
+        .map_err(|source| LoadConfigError::DeserializeConfig { source })?;
+
+    Ok(Loaded {
+        config,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use ts_error::diagnostic::{Diagnostic, Diagnostics};
+
+    use crate::{
+        ConfigFile, LoadConfigError, Loaded, config_schema, lint_against_schema,
+        load::{into_loaded, lint},
+        load_report, try_load_in,
+    };
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Config {
+        field_a: usize,
+    }
+    impl ConfigFile for Config {
+        fn config_file_path() -> PathBuf {
+            env::temp_dir().join("ts-config-load-report.json")
+        }
+    }
+
+    #[test]
+    fn loads_clean_config() {
+        Config { field_a: 5 }
+            .write()
+            .expect("writing config should succeed");
+
+        let loaded = load_report::<Config>().expect("config should load");
+        assert_eq!(Config { field_a: 5 }, loaded.config);
+        assert!(loaded.diagnostics.is_empty());
+
+        Config::default()
+            .delete()
+            .expect("deleting config should succeed");
+    }
+
+    #[test]
+    fn loads_config_with_comments() {
+        let source = "{\n  // the field\n  \"field_a\": /* five */ 5\n}";
+        let diagnostics = lint::<Config>(source, None).expect("linting should succeed");
+        assert!(diagnostics.is_empty());
+        let loaded: Loaded<Config> = into_loaded(source, diagnostics).expect("config should load");
+        assert_eq!(Config { field_a: 5 }, loaded.config);
+
+        let source = "{\n  // the field\n  \"field_a\": \"five\"\n}";
+        let diagnostics = lint::<Config>(source, None).expect("linting should succeed");
+        let context = diagnostics
+            .problems
+            .first()
+            .and_then(|problem| problem.context.as_ref())
+            .expect("problem should have context");
+        assert_eq!(
+            vec!["{", "  // the field", "  \"field_a\": \"five\""],
+            context.context
+        );
+    }
+
+    #[test]
+    fn loads_config_with_warnings() {
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        diagnostics.push(Diagnostic::warning("some warning"));
+
+        let loaded: Loaded<Config> =
+            into_loaded(r#"{ "field_a": 2 }"#, diagnostics).expect("config should load");
+        assert_eq!(Config { field_a: 2 }, loaded.config);
+        assert_eq!(1, loaded.diagnostics.warnings().count());
+
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        diagnostics.push(Diagnostic::error("some error"));
+
+        let result: Result<Loaded<Config>, _> = into_loaded(r#"{ "field_a": 2 }"#, diagnostics);
+        assert!(matches!(result, Err(LoadConfigError::InvalidConfig { .. })));
+    }
+
+    #[test]
+    fn lints_against_schema_file() {
+        let directory = env::temp_dir().join("ts-config-lint-against-schema");
+        fs::create_dir_all(&directory).expect("creating directory should succeed");
+
+        let schema_path = directory.join("config.schema.json");
+        let config_path = directory.join("config.json");
+        fs::write(
+            &schema_path,
+            r#"{ "type": "object", "properties": { "port": { "type": "integer" } }, "required": ["port", "host"] }"#,
+        )
+        .expect("writing schema should succeed");
+        fs::write(&config_path, r#"{ "port": "eighty" }"#).expect("writing config should succeed");
+
+        let result = lint_against_schema(&config_path, &schema_path);
+        let missing_schema = lint_against_schema(&config_path, &directory.join("missing.json"));
+
+        fs::remove_dir_all(&directory).expect("removing directory should succeed");
+
+        let diagnostics = result.expect("linting should succeed");
+        assert_eq!(2, diagnostics.errors().count());
+        assert!(
+            diagnostics
+                .problems
+                .iter()
+                .all(|problem| problem.file_path == Some(config_path.display().to_string()))
+        );
+        assert!(matches!(
+            missing_schema,
+            Err(LoadConfigError::ReadSchema { .. })
+        ));
+    }
+
+    #[test]
+    fn stamps_schema_id() {
+        #[derive(Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+        struct IdentifiedConfig {
+            field_a: usize,
+        }
+        impl ConfigFile for IdentifiedConfig {
+            fn config_file_path() -> PathBuf {
+                env::temp_dir().join("ts-config-schema-id.json")
+            }
+
+            fn json_schema_id() -> Option<&'static str> {
+                Some("https://example.com/config.schema.json")
+            }
+        }
+
+        let schema = config_schema::<IdentifiedConfig>();
+        assert_eq!(
+            Some("https://example.com/config.schema.json"),
+            schema.get("$id").and_then(|id| id.as_str())
+        );
+        assert!(schema.get("$schema").is_some());
+
+        assert!(config_schema::<Config>().get("$id").is_none());
+    }
+
+    #[test]
+    fn loads_relative_to_base() {
+        #[derive(
+            Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+        )]
+        struct RelativeConfig {
+            field_a: usize,
+        }
+        impl ConfigFile for RelativeConfig {
+            fn config_file_path() -> PathBuf {
+                PathBuf::from("relative-config.json")
+            }
+        }
+
+        let base = env::temp_dir().join("ts-config-load-in");
+        fs::create_dir_all(&base).expect("creating directory should succeed");
+        let config_path = base.join("relative-config.json");
+
+        fs::write(&config_path, r#"{ "field_a": 7 }"#).expect("writing config should succeed");
+        let loaded = try_load_in::<RelativeConfig>(&base);
+
+        fs::write(&config_path, r#"{ "field_a": "seven" }"#)
+            .expect("writing config should succeed");
+        let invalid = try_load_in::<RelativeConfig>(&base);
+
+        fs::remove_dir_all(&base).expect("removing directory should succeed");
+
+        assert_eq!(
+            RelativeConfig { field_a: 7 },
+            loaded.expect("config should load")
+        );
+
+        let Err(LoadConfigError::InvalidConfig {
+            source: diagnostics,
+        }) = invalid
+        else {
+            panic!("config should be invalid");
+        };
+        assert!(
+            diagnostics
+                .problems
+                .iter()
+                .all(|problem| problem.file_path == Some(config_path.display().to_string()))
+        );
+    }
+}
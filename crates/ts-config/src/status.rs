@@ -0,0 +1,130 @@
+//! Check the status of a config file.
+
+use std::{fs, path::PathBuf};
+
+use ts_error::diagnostic::Diagnostics;
+use ts_json::{strip_comments, syntax_diagnostic};
+
+use crate::{ConfigFile, load::read_source};
+
+/// The status of a config file.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ConfigStatus {
+    /// The path to the config file.
+    pub path: PathBuf,
+    /// If the config file exists.
+    pub exists: bool,
+    /// If the config file is valid JSON.
+    pub parses: bool,
+    /// If the config file has no errors and can be loaded.
+    pub valid: bool,
+    /// The diagnostics from parsing and linting the config file.
+    pub diagnostics: Diagnostics,
+}
+
+/// Check the status of a config file. Each check is performed even if an earlier check fails,
+/// where possible.
+pub fn status<C: ConfigFile>() -> ConfigStatus {
+    let path = C::config_file_path();
+    let mut status = ConfigStatus {
+        exists: fs::exists(&path).unwrap_or(false),
+        path,
+        parses: false,
+        valid: false,
+        diagnostics: Diagnostics::new("validating JSON"),
+    };
+
+    if !status.exists {
+        return status;
+    }
+
+    let Ok(config_source) = read_source::<C>() else {
+        return status;
+    };
+
+    let stripped = strip_comments(&config_source.source);
+    if let Err(error) = serde_json::from_str::<serde_json::Value>(&stripped) {
+        let diagnostic = syntax_diagnostic(
+            &error,
+            &config_source.source,
+            "config file is not valid JSON",
+        )
+        .file_path(config_source.path.display());
+        status.diagnostics.push(diagnostic);
+        return status;
+    }
+    status.parses = true;
+
+    if let Ok(diagnostics) = config_source.lint::<C>() {
+        status.valid = !diagnostics.has_errors() && serde_json::from_str::<C>(&stripped).is_ok();
+        status.diagnostics = diagnostics;
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::{ConfigFile, status};
+
+    #[derive(
+        Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+    )]
+    struct Config {
+        field_a: usize,
+    }
+    impl ConfigFile for Config {
+        fn config_file_path() -> PathBuf {
+            env::temp_dir().join("ts-config-status.json")
+        }
+    }
+
+    #[test]
+    fn reports_status() {
+        let _ = fs::remove_file(Config::config_file_path());
+        let absent = status::<Config>();
+
+        Config { field_a: 5 }
+            .write()
+            .expect("writing config should succeed");
+        let valid = status::<Config>();
+
+        fs::write(
+            Config::config_file_path(),
+            "{\n  // The value of field A.\n  \"field_a\": 5\n}",
+        )
+        .expect("writing config should succeed");
+        let commented = status::<Config>();
+
+        fs::write(Config::config_file_path(), r#"{ "field_a": "five" }"#)
+            .expect("writing config should succeed");
+        let invalid = status::<Config>();
+
+        fs::write(Config::config_file_path(), r#"{ "field_a": "#)
+            .expect("writing config should succeed");
+        let malformed = status::<Config>();
+
+        Config::default()
+            .delete()
+            .expect("deleting config should succeed");
+
+        assert_eq!(Config::config_file_path(), absent.path);
+        assert!(!absent.exists && !absent.parses && !absent.valid);
+        assert!(absent.diagnostics.is_empty());
+
+        assert!(valid.exists && valid.parses && valid.valid);
+        assert!(valid.diagnostics.is_empty());
+
+        assert!(commented.exists && commented.parses && commented.valid);
+        assert!(commented.diagnostics.is_empty());
+
+        assert!(invalid.exists && invalid.parses && !invalid.valid);
+        assert_eq!(1, invalid.diagnostics.errors().count());
+
+        assert!(malformed.exists && !malformed.parses && !malformed.valid);
+        assert_eq!(1, malformed.diagnostics.errors().count());
+    }
+}
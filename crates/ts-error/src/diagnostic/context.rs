@@ -1,54 +1,116 @@
 //! Context for a diagnostic.
 
-use crate::diagnostic::Span;
+use crate::diagnostic::{SourceIndex, Span};
 
 use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The maximum number of graphemes in a line of context.
+const MAX_LENGTH: usize = 100;
+
+/// The number of lines of source before the line of the span that are shown by default.
+const LINES_BEFORE: usize = 2;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Context for a diagnostic.
 pub struct Context {
     /// The context for the diagnostic, sequential lines of the source where the last string is the
-    /// relevant line for the diagnostic. Each line is at most 100 characters wide
+    /// relevant line for the diagnostic. Each line is at most 100 graphemes
     pub context: Vec<String>,
+    /// For spans that cover multiple lines, the lines of the source following the relevant line up
+    /// to and including the line the span ends on. Each line is at most 100 graphemes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub span_lines: Vec<String>,
+    /// Sequential lines of the source following the relevant line for the diagnostic. Each line
+    /// is at most 100 graphemes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lines_after: Vec<String>,
     /// The span of the context relevant to the diagnostic.
     pub span: Span,
     /// The label for the span.
     pub label: Option<String>,
-    /// How indented into the context the span starts.
+    /// How many graphemes into the context the span starts.
     pub span_indent: usize,
 }
 impl Context {
     /// Create the context for a diagnostic from a span and the source file.
+    ///
+    /// If the span starts past the end of its line, it is clamped to the end of the line.
     pub fn new(source: &str, span: Span) -> Self {
-        const MAX_LENGTH: usize = 100;
+        Self::from_index(&SourceIndex::new(source), span)
+    }
+
+    /// Create the context for a diagnostic from a span and an index of the source file.
+    ///
+    /// Building the index once and reusing it avoids splitting the source into lines for each
+    /// diagnostic over the same source.
+    pub fn from_index(index: &SourceIndex<'_>, span: Span) -> Self {
+        Self::from_index_with_lines(index, span, LINES_BEFORE)
+    }
+
+    /// Create the context for a diagnostic from a span and the source file, showing up to `before`
+    /// lines of source before the line of the span, and up to `after` lines following the span.
+    ///
+    /// Lines before the start or past the end of the source are omitted.
+    pub fn with_lines(source: &str, span: Span, before: usize, after: usize) -> Self {
+        Self::from_index_with_lines(&SourceIndex::new(source), span, before)
+            .lines_after(source, after)
+    }
+
+    /// Create the context for a diagnostic from a span and an index of the source file, showing up
+    /// to `before` lines of source before the line of the span.
+    fn from_index_with_lines(index: &SourceIndex<'_>, mut span: Span, before: usize) -> Self {
+        if let Some(line) = span.line.checked_sub(1).and_then(|line| index.line(line)) {
+            let graphemes = line.graphemes(true).count();
+            if span.column > graphemes + 1 {
+                span.column = graphemes + 1;
+                span.length = 1;
+            }
+        }
 
         let context_end = span.column.saturating_sub(1) + span.length.min(MAX_LENGTH);
         let context_start = span.column.saturating_sub(1);
 
         let span_start = context_start
             .saturating_sub(MAX_LENGTH.saturating_sub(context_end.saturating_sub(context_start)));
-        let span_end = span_start + MAX_LENGTH;
-
-        let mut context = Vec::with_capacity(3);
-        let lines: Vec<&str> = source.lines().collect();
-        for i in (1..4).rev() {
-            if let Some(index) = span.line.checked_sub(i)
-                && let Some(line) = lines.get(index)
-            {
-                let line_context = line
-                    .get(span_start..span_end.min(line.len()))
-                    .unwrap_or_default();
-                context.push(line_context.to_string());
+
+        let mut context = Vec::with_capacity(before.saturating_add(1).min(span.line));
+        for i in (1..=before.saturating_add(1)).rev() {
+            if let Some(line) = span.line.checked_sub(i).and_then(|line| index.line(line)) {
+                context.push(clip_line(line, span_start));
             }
         }
 
+        let span_lines = (span.line..span.last_line())
+            .filter_map(|line| index.line(line))
+            .map(|line| clip_line(line, span_start))
+            .collect();
+
         let span_indent = context_start.saturating_sub(span_start);
 
         Self {
             context,
+            span_lines,
+            lines_after: Vec::new(),
+            span,
+            label: None,
+            span_indent,
+        }
+    }
+
+    /// Create the context for a diagnostic from lines that have already been extracted from a
+    /// source, where the last line is the relevant line for the diagnostic.
+    ///
+    /// The lines are used as is, they are not clipped.
+    pub fn from_parts(lines: Vec<String>, span: Span, span_indent: usize) -> Self {
+        Self {
+            context: lines,
+            span_lines: Vec::new(),
+            lines_after: Vec::new(),
             span,
             label: None,
             span_indent,
@@ -60,13 +122,61 @@ impl Context {
         self.label = Some(label.to_string());
         self
     }
+
+    /// Sets the lines of the source following the relevant line to show, up to `count` lines.
+    pub fn lines_after(mut self, source: &str, count: usize) -> Self {
+        let line_start = self.line_start();
+
+        self.lines_after = source
+            .lines()
+            .skip(self.span.last_line())
+            .take(count)
+            .map(|line| clip_line(line, line_start))
+            .collect();
+
+        self
+    }
+
+    /// Returns the line number of the last line of the context.
+    pub(crate) fn last_line(&self) -> usize {
+        self.span.last_line() + self.lines_after.len()
+    }
+
+    /// Returns the number of graphemes to highlight on the line the span ends on, for spans that
+    /// cover multiple lines.
+    pub(crate) fn end_length(&self) -> usize {
+        self.span
+            .end
+            .map_or(1, |(_, column)| column.saturating_sub(self.line_start()))
+            .clamp(1, MAX_LENGTH)
+    }
+
+    /// Returns the zero-indexed column each line of context starts at in the source.
+    pub(crate) fn line_start(&self) -> usize {
+        self.span
+            .column
+            .saturating_sub(1)
+            .saturating_sub(self.span_indent)
+    }
+}
+
+/// Clip a line of source to at most [`MAX_LENGTH`] graphemes, starting from the grapheme `start`.
+fn clip_line(line: &str, start: usize) -> String {
+    line.graphemes(true).skip(start).take(MAX_LENGTH).collect()
 }
 
 #[cfg(test)]
 mod test {
-    use alloc::{string::String, vec, vec::Vec};
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use ts_ansi::style::{BOLD, CYAN, RED, RESET};
 
-    use crate::diagnostic::{Context, Span};
+    use crate::diagnostic::{Context, Diagnostic, DisplayStyle, SourceIndex, Span};
 
     const SOURCE: &str = r#"use alloc::boxed::Box;
 use core::{error::Error, fmt};
@@ -144,7 +254,14 @@ impl fmt::Display for Report<'_> {
 
         let span = Span::default().line(35).column(999).length(999);
         let context = Context::new(SOURCE, span);
-        assert_eq!(vec![r#""#, r#""#, r#""#], context.context);
+        assert_eq!(
+            vec![
+                r#""#,
+                r#"        while let Some(error) = current_error {"#,
+                r#"            writeln!(f, " {BOLD}{RED}{count}{DEFAULT}.{RESET} {error}")?;"#,
+            ],
+            context.context
+        );
 
         let span = Span::default().line(1).column(200).length(50);
         let context = Context::new(MINIFIED_SOURCE, span);
@@ -155,4 +272,142 @@ impl fmt::Display for Report<'_> {
             context.context
         );
     }
+
+    #[test]
+    fn shows_configured_lines() {
+        let span = Span::default().line(7).column(12).length(6);
+        let context = Context::with_lines(SOURCE, span, 0, 2);
+        assert_eq!(vec![r#"pub struct Report<'e> {"#], context.context);
+        assert_eq!(
+            vec![
+                r#"    /// The error for this report."#,
+                r#"    pub source: Box<dyn Error + 'e>,"#
+            ],
+            context.lines_after
+        );
+
+        let span = Span::default().line(2);
+        let context = Context::with_lines(SOURCE, span, 10, 0);
+        assert_eq!(
+            vec![
+                r#"use alloc::boxed::Box;"#,
+                r#"use core::{error::Error, fmt};"#
+            ],
+            context.context
+        );
+
+        let span = Span::default().line(42);
+        let context = Context::with_lines(SOURCE, span, 1, 5);
+        assert_eq!(vec![r#"        Ok(())"#, r#"    }"#], context.context);
+        assert_eq!(vec![r#"}"#], context.lines_after);
+
+        let span = Span::default().line(36).column(5);
+        assert_eq!(
+            Context::new(SOURCE, span).context,
+            Context::with_lines(SOURCE, span, 2, 0).context
+        );
+    }
+
+    #[test]
+    fn handles_lines_after() {
+        let span = Span::default().line(41).column(5).length(2);
+        let context = Context::new(SOURCE, span).lines_after(SOURCE, 2);
+        assert_eq!(vec![r#"    }"#, r#"}"#], context.lines_after);
+        assert_eq!(43, context.last_line());
+
+        let span = Span::default().line(43);
+        let context = Context::new(SOURCE, span).lines_after(SOURCE, 2);
+        assert_eq!(Vec::<String>::new(), context.lines_after);
+    }
+
+    #[test]
+    fn handles_multiline_span() {
+        let span = Span::default().line(13).column(52).end(17, 5);
+        let context = Context::new(SOURCE, span).lines_after(SOURCE, 1);
+        assert_eq!(
+            vec![
+                r#"        Self {"#,
+                r#"            source: Box::new(source),"#,
+                r#"        }"#,
+                r#"    }"#,
+            ],
+            context.span_lines
+        );
+        assert_eq!(vec![r#"}"#], context.lines_after);
+        assert_eq!(18, context.last_line());
+        assert_eq!(5, context.end_length());
+    }
+
+    #[test]
+    fn clamps_overlong_column() {
+        let line = r#"pub struct Report<'e> {"#;
+
+        let span = Span::default().line(7).column(80).length(4);
+        let context = Context::new(SOURCE, span);
+        assert_eq!(Some(line), context.context.last().map(String::as_str));
+        assert_eq!(line.len(), context.span_indent);
+        assert_eq!(line.len() + 1, context.span.column);
+        assert_eq!(1, context.span.length);
+
+        let span = Span::default().line(1).column(9).length(2);
+        let context = Context::new("\"다람쥐\"", span);
+        assert_eq!(
+            Some("\"다람쥐\""),
+            context.context.last().map(String::as_str)
+        );
+        assert_eq!(5, context.span_indent);
+        assert_eq!(6, context.span.column);
+    }
+
+    #[test]
+    fn builds_from_parts() {
+        let context = Context::from_parts(
+            vec!["[record]".into(), "name = 5".into()],
+            Span::default().line(4).column(8),
+            7,
+        )
+        .label("expected a string");
+
+        let output = Diagnostic::error("some headline here")
+            .context(context)
+            .render(DisplayStyle::Color)
+            .to_string();
+
+        for line in [
+            format!("{CYAN}{BOLD}3 | {RESET}[record]"),
+            format!("{CYAN}{BOLD}4 | {RESET}name = 5"),
+            format!(" {CYAN}{BOLD} | {RESET}       {RED}{BOLD}^ expected a string{RESET}"),
+        ] {
+            assert!(
+                output.contains(&format!("{line}\n")),
+                "missing `{line}` in {output:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reuses_source_index() {
+        let index = SourceIndex::new(SOURCE);
+
+        for line in 1..=index.line_count() + 1 {
+            for column in [1, 4, 12, 80] {
+                let span = Span::default().line(line).column(column).length(3);
+
+                let indexed = Diagnostic::error("headline")
+                    .context(Context::from_index(&index, span))
+                    .to_string();
+                let direct = Diagnostic::error("headline")
+                    .context(Context::new(SOURCE, span))
+                    .to_string();
+
+                assert_eq!(direct, indexed);
+            }
+        }
+
+        let span = Span::default().line(2).column(8).end(5, 3);
+        assert_eq!(
+            Context::new(SOURCE, span).span_lines,
+            Context::from_index(&index, span).span_lines
+        );
+    }
 }
@@ -3,9 +3,11 @@
 //! A diagnostic over some source file.
 
 mod context;
+mod source_index;
 mod span;
 
 use alloc::{
+    format,
     string::{String, ToString},
     vec,
     vec::Vec,
@@ -13,19 +15,37 @@ use alloc::{
 use core::fmt::Write;
 
 use ts_ansi::{
-    format_error, format_warning,
-    style::{BOLD, CYAN, DEFAULT, RED, RESET, YELLOW},
+    StripStyles, format_error, format_warning,
+    style::{BLUE, BOLD, CYAN, DEFAULT, DIM, RED, RESET, YELLOW},
+    wrap, write_styled,
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub use context::Context;
+pub use source_index::SourceIndex;
 pub use span::Span;
 
+/// Every severity, in the order diagnostics are displayed.
+const SEVERITIES: [Severity; 4] = [
+    Severity::Error,
+    Severity::Warning,
+    Severity::Info,
+    Severity::Help,
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
-/// A diagnostic severity.
+/// A diagnostic severity, errors are ordered before warnings, warnings before info, and info before
+/// help.
 pub enum Severity {
     /// An error.
     Error,
+    /// A suggestion for the user, such as how to fix a problem.
+    Help,
+    /// Information that is not a problem, but may be useful.
+    Info,
     /// A warning.
     Warning,
 }
@@ -35,6 +55,27 @@ impl Severity {
         match &self {
             Self::Error => RED,
             Self::Warning => YELLOW,
+            Self::Info => CYAN,
+            Self::Help => BLUE,
+        }
+    }
+
+    /// Return the noun for a diagnostic of the severity.
+    pub(crate) fn noun(self) -> &'static str {
+        match &self {
+            Self::Info => "info message",
+            Self::Help => "help message",
+            _ => self.word(),
+        }
+    }
+
+    /// Return the rank of the severity, the most severe is ranked first.
+    fn rank(self) -> u8 {
+        match &self {
+            Self::Error => 0,
+            Self::Warning => 1,
+            Self::Info => 2,
+            Self::Help => 3,
         }
     }
 
@@ -43,11 +84,69 @@ impl Severity {
         match &self {
             Self::Error => "error",
             Self::Warning => "warning",
+            Self::Info => "info",
+            Self::Help => "help",
         }
     }
 }
+impl PartialOrd for Severity {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Severity {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
 
-#[derive(Debug)]
+/// How to render diagnostics, see [`Diagnostic::render`] and [`Diagnostics::render`].
+///
+/// Unlike displaying diagnostics directly, rendering ignores if output should be styled, see
+/// [`ts_ansi::styling_enabled`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisplayStyle {
+    /// Styled with ANSI escapes.
+    #[default]
+    Color,
+    /// Without any ANSI escapes.
+    Plain,
+}
+
+/// How to finalize a collection of diagnostics before displaying it, see [`Diagnostics::finalize`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FinalizeOptions {
+    /// The maximum number of diagnostics to keep, see [`Diagnostics::cap`].
+    pub cap: Option<usize>,
+    /// Remove duplicate diagnostics, see [`Diagnostics::dedup`].
+    pub dedup: bool,
+    /// Sort the diagnostics, see [`Diagnostics::sort`].
+    pub sort: bool,
+}
+impl FinalizeOptions {
+    /// Keep at most `max` diagnostics, see [`Diagnostics::cap`].
+    pub fn cap(mut self, max: usize) -> Self {
+        self.cap = Some(max);
+        self
+    }
+
+    /// Remove duplicate diagnostics, see [`Diagnostics::dedup`].
+    pub fn dedup(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+
+    /// Sort the diagnostics, see [`Diagnostics::sort`].
+    pub fn sort(mut self) -> Self {
+        self.sort = true;
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A collection of diagnostics
 pub struct Diagnostics {
     /// The problems.
@@ -56,6 +155,23 @@ pub struct Diagnostics {
     pub context: String,
 }
 impl Diagnostics {
+    /// Keep at most the first `max` diagnostics.
+    pub fn cap(&mut self, max: usize) {
+        self.problems.truncate(max);
+    }
+
+    /// Remove diagnostics that duplicate an earlier diagnostic, keeping the first. Diagnostics are
+    /// duplicates if they have the same severity, code, headline, file path, and span.
+    pub fn dedup(&mut self) {
+        let problems = core::mem::take(&mut self.problems);
+
+        for problem in problems {
+            if !self.problems.iter().any(|kept| kept.is_duplicate(&problem)) {
+                self.problems.push(problem);
+            }
+        }
+    }
+
     /// Create a new collection of diagnostics.
     pub fn new<S: ToString>(context: S) -> Self {
         Self {
@@ -64,16 +180,116 @@ impl Diagnostics {
         }
     }
 
+    /// Create a collection of diagnostics containing a single diagnostic.
+    pub fn from_single<S: ToString>(context: S, diagnostic: Diagnostic) -> Self {
+        Self {
+            problems: vec![diagnostic],
+            context: context.to_string(),
+        }
+    }
+
+    /// Set the context of the collection.
+    pub fn with_context<S: ToString>(mut self, context: S) -> Self {
+        self.context = context.to_string();
+        self
+    }
+
     /// Returns if there are no diagnostics.
     pub fn is_empty(&self) -> bool {
         self.problems.is_empty()
     }
 
+    /// Returns if there are any error diagnostics.
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Returns if there are any warning diagnostics.
+    pub fn has_warnings(&self) -> bool {
+        self.warnings().next().is_some()
+    }
+
+    /// Returns an iterator over the help diagnostics.
+    pub fn helps(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == Severity::Help)
+    }
+
     /// Push a diagnostic into this collection.
     pub fn push(&mut self, diagnostic: Diagnostic) {
         self.problems.push(diagnostic);
     }
 
+    /// Returns an iterator over the diagnostics that have context, paired with their span.
+    pub fn spans(&self) -> impl Iterator<Item = (&Diagnostic, Span)> {
+        self.problems.iter().filter_map(|problem| {
+            problem
+                .context
+                .as_ref()
+                .map(|context| (problem, context.span))
+        })
+    }
+
+    /// Returns a displayable version of the diagnostics that omits the trailing summary of how many
+    /// diagnostics of each severity were generated.
+    pub fn display_without_summary(&self) -> impl core::fmt::Display + '_ {
+        WithoutSummary(self)
+    }
+
+    /// Returns a displayable version of the diagnostics grouped by their file path, each group is
+    /// preceded by a header summarising the group:
+    /// `some/path/to/a.file: 2 errors, 1 warning`
+    ///
+    /// Diagnostics without a file path are displayed last, without a header.
+    pub fn display_grouped_by_file(&self) -> impl core::fmt::Display + '_ {
+        GroupedByFile(self)
+    }
+
+    /// Returns a displayable version of the diagnostics in a display style. The plain style has
+    /// the same layout as the coloured style, without the ANSI escapes.
+    pub fn render(&self, style: DisplayStyle) -> impl core::fmt::Display + '_ {
+        Rendered(self, style)
+    }
+
+    /// Write the diagnostics without any ANSI escapes, e.g., for a log file.
+    pub fn write_plain(&self, w: &mut impl Write) -> core::fmt::Result {
+        write!(w, "{}", self.render(DisplayStyle::Plain))
+    }
+
+    /// Move the diagnostics from another collection into this collection.
+    pub fn extend(&mut self, other: Self) {
+        self.problems.extend(other.problems);
+    }
+
+    /// Sort, deduplicate, then cap the diagnostics, depending on the options. Capping last keeps
+    /// the most severe diagnostics when the diagnostics are sorted.
+    pub fn finalize(mut self, options: FinalizeOptions) -> Self {
+        if options.sort {
+            self.sort();
+        }
+        if options.dedup {
+            self.dedup();
+        }
+        if let Some(max) = options.cap {
+            self.cap(max);
+        }
+
+        self
+    }
+
+    /// Sort the diagnostics by severity, then by location. Diagnostics without context are ordered
+    /// last within their severity, the sort is stable.
+    pub fn sort(&mut self) {
+        self.problems.sort_by_key(|problem| {
+            (
+                problem.severity,
+                problem.context.is_none(),
+                problem.context.as_ref().map(|context| context.span),
+            )
+        });
+    }
+
     /// Returns an iterator over the error diagnostics.
     pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
         self.problems
@@ -87,52 +303,209 @@ impl Diagnostics {
             .iter()
             .filter(|problem| problem.severity == Severity::Warning)
     }
+
+    /// Returns an iterator over the info diagnostics.
+    pub fn infos(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == Severity::Info)
+    }
+
+    /// Serialize the diagnostics as JSON, for consumption by other programs.
+    ///
+    /// Spans are one-indexed, matching the locations in the displayed diagnostics.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
 }
-impl core::fmt::Display for Diagnostics {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let warnings: Vec<_> = self.warnings().collect();
-        let errors: Vec<_> = self.errors().collect();
+impl Diagnostics {
+    /// Write the diagnostics with styling, followed by the summary.
+    fn write_to(&self, f: &mut dyn Write) -> core::fmt::Result {
+        self.write_problems(f)?;
 
-        for error in &errors {
-            writeln!(f, "{error}")?;
-        }
-        for warning in &warnings {
-            writeln!(f, "{warning}")?;
-        }
+        let warnings = self.warnings().count();
+        let errors = self.errors().count();
+        let infos = self.infos().count();
+        let helps = self.helps().count();
+        let warning_codes = codes(self.warnings());
+        let error_codes = codes(self.errors());
+        let info_codes = codes(self.infos());
+        let help_codes = codes(self.helps());
+
+        // Collections without context are summarised as `generated 2 errors`.
+        let prefix = if self.context.is_empty() {
+            String::new()
+        } else {
+            format!("{} ", self.context)
+        };
 
-        if !errors.is_empty() {
+        if errors != 0 {
             writeln!(
                 f,
                 "{}",
-                format_error!("{} generated {} errors", self.context, errors.len())
+                format_error!("{prefix}generated {errors} errors{error_codes}")
             )?;
         }
-        if !warnings.is_empty() {
+        if warnings != 0 {
             writeln!(
                 f,
                 "{}",
-                format_warning!("{} generated {} warnings", self.context, warnings.len())
+                format_warning!("{prefix}generated {warnings} warnings{warning_codes}")
+            )?;
+        }
+        if infos != 0 {
+            writeln!(
+                f,
+                "{BOLD}{CYAN}info{DEFAULT}:{RESET} {prefix}generated {}{info_codes}",
+                pluralize(infos, Severity::Info.noun())
+            )?;
+        }
+        if helps != 0 {
+            writeln!(
+                f,
+                "{BOLD}{BLUE}help{DEFAULT}:{RESET} {prefix}generated {}{help_codes}",
+                pluralize(helps, Severity::Help.noun())
             )?;
         }
 
         Ok(())
     }
+
+    /// Write the diagnostics with styling, errors first, then warnings, info, and help.
+    fn write_problems(&self, f: &mut dyn Write) -> core::fmt::Result {
+        for severity in SEVERITIES {
+            for problem in self
+                .problems
+                .iter()
+                .filter(|problem| problem.severity == severity)
+            {
+                problem.write_to(f, false)?;
+                f.write_char('\n')?;
+            }
+        }
+
+        Ok(())
+    }
+}
+impl core::fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_styled(f, |f| self.write_to(f))
+    }
 }
 impl core::error::Error for Diagnostics {}
 
+/// Displays a collection of diagnostics without the summary, see
+/// [`Diagnostics::display_without_summary`].
+struct WithoutSummary<'d>(&'d Diagnostics);
+impl core::fmt::Display for WithoutSummary<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_styled(f, |f| self.0.write_problems(f))
+    }
+}
+
+/// A diagnostic or collection of diagnostics that can be rendered in a display style.
+trait Render {
+    /// Write with styling, the styling is removed for the plain display style.
+    fn render_to(&self, f: &mut dyn Write) -> core::fmt::Result;
+}
+impl Render for Diagnostic {
+    fn render_to(&self, f: &mut dyn Write) -> core::fmt::Result {
+        self.write_to(f, false)
+    }
+}
+impl Render for Diagnostics {
+    fn render_to(&self, f: &mut dyn Write) -> core::fmt::Result {
+        self.write_to(f)
+    }
+}
+
+/// Displays a diagnostic or collection of diagnostics in a display style, see
+/// [`Diagnostic::render`].
+struct Rendered<'d, T>(&'d T, DisplayStyle);
+impl<T: Render> core::fmt::Display for Rendered<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.1 {
+            DisplayStyle::Color => self.0.render_to(f),
+            DisplayStyle::Plain => self.0.render_to(&mut StripStyles::new(f)),
+        }
+    }
+}
+
+/// Displays a collection of diagnostics grouped by file path, see
+/// [`Diagnostics::display_grouped_by_file`].
+struct GroupedByFile<'d>(&'d Diagnostics);
+impl core::fmt::Display for GroupedByFile<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_styled(f, |f| {
+            let mut file_paths: Vec<Option<&str>> = Vec::new();
+            for problem in &self.0.problems {
+                let file_path = problem.file_path.as_deref();
+                if !file_paths.contains(&file_path) {
+                    file_paths.push(file_path);
+                }
+            }
+            // Diagnostics without a file path are displayed last.
+            file_paths.sort_by_key(Option::is_none);
+
+            for file_path in file_paths {
+                let group: Vec<&Diagnostic> = self
+                    .0
+                    .problems
+                    .iter()
+                    .filter(|problem| problem.file_path.as_deref() == file_path)
+                    .collect();
+
+                if let Some(file_path) = file_path {
+                    let mut counts = Vec::with_capacity(4);
+                    for severity in SEVERITIES {
+                        let count = group
+                            .iter()
+                            .filter(|problem| problem.severity == severity)
+                            .count();
+                        if count != 0 {
+                            counts.push(pluralize(count, severity.noun()));
+                        }
+                    }
+                    writeln!(f, "{BOLD}{file_path}{RESET}: {}", counts.join(", "))?;
+                }
+
+                for severity in SEVERITIES {
+                    for problem in group.iter().filter(|problem| problem.severity == severity) {
+                        problem.write_to(f, false)?;
+                        f.write_char('\n')?;
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A diagnostic over some source file.
 pub struct Diagnostic {
     /// The diagnostic severity.
     pub severity: Severity,
+    /// The diagnostic code, used to identify the rule or problem.
+    pub code: Option<String>,
     /// The diagnostic headline.
     pub headline: String,
     /// The diagnostic filepath.
     pub file_path: Option<String>,
     /// The diagnostic context.
     pub context: Option<Context>,
+    /// Other places in the source that are related to the diagnostic, e.g., where something was
+    /// first defined.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub related: Vec<Context>,
     /// The nodes.
     pub notes: Vec<String>,
+    /// The width to wrap the notes to.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub max_width: Option<usize>,
 }
 
 impl Diagnostic {
@@ -140,10 +513,13 @@ impl Diagnostic {
     pub fn new<S: ToString>(severity: Severity, headling: S) -> Self {
         Self {
             severity,
+            code: None,
             headline: headling.to_string(),
             file_path: None,
             context: None,
+            related: Vec::new(),
             notes: Vec::new(),
+            max_width: None,
         }
     }
 
@@ -151,24 +527,57 @@ impl Diagnostic {
     pub fn error<S: ToString>(headling: S) -> Self {
         Self {
             severity: Severity::Error,
+            code: None,
             headline: headling.to_string(),
             file_path: None,
             context: None,
+            related: Vec::new(),
             notes: Vec::new(),
+            max_width: None,
         }
     }
 
+    /// Create a help diagnostic, for suggestions that are not problems themselves.
+    pub fn help<S: ToString>(headling: S) -> Self {
+        Self::new(Severity::Help, headling)
+    }
+
     /// Create a warning diagnostic.
     pub fn warning<S: ToString>(headling: S) -> Self {
         Self {
             severity: Severity::Warning,
+            code: None,
             headline: headling.to_string(),
             file_path: None,
             context: None,
+            related: Vec::new(),
             notes: Vec::new(),
+            max_width: None,
         }
     }
 
+    /// Create an info diagnostic.
+    pub fn info<S: ToString>(headling: S) -> Self {
+        Self::new(Severity::Info, headling)
+    }
+
+    /// Returns if this diagnostic has the same severity, code, headline, file path, and span as
+    /// another diagnostic.
+    fn is_duplicate(&self, other: &Self) -> bool {
+        self.severity == other.severity
+            && self.code == other.code
+            && self.headline == other.headline
+            && self.file_path == other.file_path
+            && self.context.as_ref().map(|context| context.span)
+                == other.context.as_ref().map(|context| context.span)
+    }
+
+    /// Set the code of the diagnostic.
+    pub fn code<S: ToString>(mut self, code: S) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
     /// Set the filepath of the diagnostic.
     pub fn file_path<S: ToString>(mut self, path: S) -> Self {
         self.file_path = Some(path.to_string());
@@ -181,30 +590,132 @@ impl Diagnostic {
         self
     }
 
+    /// Add notes to the diagnostic in order, skipping any note that the diagnostic already has.
+    pub fn merge_notes_from<I, S>(&mut self, notes: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        for note in notes {
+            let note = note.to_string();
+            if !self.notes.contains(&note) {
+                self.notes.push(note);
+            }
+        }
+    }
+
     /// Set the context of the diagnostic.
     pub fn context(mut self, context: Context) -> Self {
         self.context = Some(context);
         self
     }
+
+    /// Set the context of the diagnostic to a span of the source file.
+    pub fn at(self, source: &str, span: Span) -> Self {
+        self.context(Context::new(source, span))
+    }
+
+    /// Add a related context to the diagnostic, e.g., where something was first defined. Related
+    /// spans are highlighted with `-` rather than `^`.
+    ///
+    /// Contexts are displayed in the order of their spans in the source, spans on the same line
+    /// share the same lines of source.
+    pub fn related(mut self, context: Context) -> Self {
+        self.related.push(context);
+        self
+    }
+
+    /// Wrap the notes so no line is wider than `width` visible columns, continuation lines are
+    /// aligned with the start of the note.
+    pub fn wrapped(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// Render the diagnostic on a single line without styling:
+    /// `some/path/to/a.file:12:2: error[CODE]: some headline here`
+    pub fn oneline(&self) -> String {
+        let mut output = String::new();
+
+        if let Some(file_path) = &self.file_path {
+            output.push_str(file_path);
+            if let Some(context) = &self.context {
+                output.push_str(&format!(":{}:{}", context.span.line, context.span.column));
+            }
+            output.push_str(": ");
+        } else if let Some(context) = &self.context {
+            output.push_str(&format!("{}:{}: ", context.span.line, context.span.column));
+        }
+
+        output.push_str(self.severity.word());
+        if let Some(code) = &self.code {
+            output.push_str(&format!("[{code}]"));
+        }
+        output.push_str(": ");
+        output.push_str(&self.headline);
+
+        output
+    }
+
+    /// Display the diagnostic with a column ruler above the context, for checking the columns of
+    /// spans while developing diagnostics.
+    pub fn display_debug(&self) -> impl core::fmt::Display + '_ {
+        DebugDisplay(self)
+    }
+
+    /// Returns a displayable version of the diagnostic in a display style. The plain style has the
+    /// same layout as the coloured style, without the ANSI escapes.
+    pub fn render(&self, style: DisplayStyle) -> impl core::fmt::Display + '_ {
+        Rendered(self, style)
+    }
+
+    /// Write the diagnostic without any ANSI escapes, e.g., for a log file.
+    pub fn write_plain(&self, w: &mut impl Write) -> core::fmt::Result {
+        write!(w, "{}", self.render(DisplayStyle::Plain))
+    }
+
+    /// Wrap the diagnostic into a collection of its own, see [`Diagnostics::from_single`].
+    pub fn into_diagnostics<S: ToString>(self, context: S) -> Diagnostics {
+        Diagnostics::from_single(context, self)
+    }
 }
 
 impl core::fmt::Display for Diagnostic {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_styled(f, |f| self.write_to(f, false))
+    }
+}
+
+impl core::error::Error for Diagnostic {}
+
+/// Displays a diagnostic with a column ruler, see [`Diagnostic::display_debug`].
+struct DebugDisplay<'d>(&'d Diagnostic);
+impl core::fmt::Display for DebugDisplay<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_styled(f, |f| self.0.write_to(f, true))
+    }
+}
+
+impl Diagnostic {
+    /// Write the diagnostic with styling, with a column ruler above the context if `is_debug`.
+    fn write_to(&self, f: &mut dyn Write, is_debug: bool) -> core::fmt::Result {
         let colour = self.severity.colour();
         let severity = self.severity.word();
 
         // Write headling:
-        // error: some headline here
-        writeln!(
-            f,
-            "{BOLD}{colour}{severity}{DEFAULT}: {}{RESET}",
-            self.headline
-        )?;
+        // error[CODE]: some headline here
+        write!(f, "{BOLD}{colour}{severity}")?;
+        if let Some(code) = &self.code {
+            write!(f, "[{code}]")?;
+        }
+        writeln!(f, "{DEFAULT}: {}{RESET}", self.headline)?;
 
-        let line_number_size = self
-            .context
-            .as_ref()
-            .map_or(1, |context| context.span.line.to_string().len());
+        let contexts = self.contexts_in_order();
+        let line_number_size = contexts
+            .iter()
+            .map(|(context, _)| context.last_line())
+            .max()
+            .map_or(1, |line| line.to_string().len());
         let indent = " ".repeat(line_number_size);
 
         // Write file path:
@@ -230,54 +741,385 @@ impl core::fmt::Display for Diagnostic {
         // Write spacer
         writeln!(f, "{indent}{CYAN}{BOLD} | {RESET}")?;
 
-        // Write context
-        if let Some(context) = &self.context {
-            // Write source lines:
-            // `98  | some source code here`
-            // `99  | some source code here`
-            // `100 | some source code here`
-            for (index, line) in context.context.iter().enumerate() {
-                let line_number = (context.span.line.saturating_sub(
-                    context
+        // Write context, contexts with spans on the same line share a block of source lines
+        let mut last_written = 0;
+        for block in blocks(&contexts) {
+            self.write_block(f, &block, line_number_size, is_debug, &mut last_written)?;
+        }
+
+        // Write notes
+        if !self.notes.is_empty() {
+            writeln!(f, "{indent}{CYAN}{BOLD} | {RESET}")?;
+            for note in &self.notes {
+                let prefix = format!("{indent}{CYAN}{BOLD} = {DEFAULT}note{RESET}: ");
+                let Some(max_width) = self.max_width else {
+                    writeln!(f, "{prefix}{note}")?;
+                    continue;
+                };
+
+                // Continuation lines are aligned with the start of the note:
+                // ` = note: some long note that`
+                // `         continues here`
+                let prefix_width = line_number_size + 9;
+                let continuation = " ".repeat(prefix_width);
+                for (index, line) in wrap(note, max_width.saturating_sub(prefix_width))
+                    .iter()
+                    .enumerate()
+                {
+                    if index == 0 {
+                        writeln!(f, "{prefix}{line}")?;
+                    } else {
+                        writeln!(f, "{continuation}{line}")?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the context and the related contexts ordered by their spans, alongside if each
+    /// context is the primary context of the diagnostic.
+    fn contexts_in_order(&self) -> Vec<(&Context, bool)> {
+        let mut contexts: Vec<_> = self
+            .context
+            .iter()
+            .map(|context| (context, true))
+            .chain(self.related.iter().map(|context| (context, false)))
+            .collect();
+        contexts.sort_by_key(|(context, _)| context.span);
+
+        contexts
+    }
+
+    /// Write a block of source lines with the spans of its contexts highlighted, where the block
+    /// is either a single context with a multiline span, or contexts with spans on the same line.
+    ///
+    /// Source lines that have already been written by an earlier block are skipped.
+    fn write_block(
+        &self,
+        f: &mut dyn Write,
+        block: &[(&Context, bool)],
+        line_number_size: usize,
+        is_debug: bool,
+        last_written: &mut usize,
+    ) -> core::fmt::Result {
+        let Some(&(context, is_primary)) = block.first() else {
+            return Ok(());
+        };
+        let indent = " ".repeat(line_number_size);
+        let (colour, marker) = self.marker(is_primary);
+        let span_line = context.context.last().map_or("", String::as_str);
+
+        // Spans that cover multiple lines are drawn in a margin between the gutter and the
+        // source.
+        let is_multiline = context.span.is_multiline();
+        let margin = if is_multiline { "  " } else { "" };
+        let continuation = format!("{colour}{BOLD}|{RESET} ");
+
+        // Write column ruler:
+        // `    | ....5....10...`
+        if is_debug {
+            let width = block
+                .iter()
+                .flat_map(|(span_context, _)| {
+                    span_context
                         .context
-                        .len()
-                        .saturating_sub(index)
-                        .saturating_sub(1),
-                ))
-                .to_string();
-                let padding = " ".repeat(line_number_size - line_number.len());
-                writeln!(f, "{CYAN}{BOLD}{line_number}{padding} | {RESET}{line}",)?;
+                        .iter()
+                        .chain(&span_context.span_lines)
+                        .chain(&span_context.lines_after)
+                })
+                .map(|line| line.width())
+                .chain(block.iter().map(|(span_context, _)| {
+                    display_width(
+                        span_line,
+                        0,
+                        block_indent(span_context, context) + span_context.span.length,
+                    )
+                }))
+                .max()
+                .unwrap_or(0);
+            writeln!(
+                f,
+                "{indent}{CYAN}{BOLD} | {RESET}{margin}{DIM}{}{RESET}",
+                ruler(context.line_start() + 1, width)
+            )?;
+        }
+
+        // Write source lines, with a gap if lines were skipped since the previous block:
+        // `98  | some source code here`
+        // `99  | some source code here`
+        // `100 | some source code here`
+        let first_line = context
+            .span
+            .line
+            .saturating_sub(context.context.len().saturating_sub(1));
+        if *last_written != 0 && first_line > *last_written + 1 {
+            writeln!(f, "{CYAN}{BOLD}...{RESET}")?;
+        }
+        for (index, line) in context.context.iter().enumerate() {
+            if first_line + index > *last_written {
+                write_source_line(f, first_line + index, line_number_size, margin, line)?;
             }
+        }
 
+        if is_multiline {
             // Write span highlighter:
             // `    |      ^^^^^^`
+            let length = span_line
+                .graphemes(true)
+                .count()
+                .saturating_sub(context.span_indent);
+            writeln!(
+                f,
+                "{indent}{CYAN}{BOLD} | {RESET}{margin}{}{colour}{BOLD}{}{RESET}",
+                " ".repeat(display_width(span_line, 0, context.span_indent)),
+                marker.repeat(display_width(span_line, context.span_indent, length).max(1))
+            )?;
+
+            // Write the rest of the lines the span covers:
+            // `101 | | some source code here`
+            // `    | ^^^^^^^^^^^^^`
+            for (index, line) in context.span_lines.iter().enumerate() {
+                write_source_line(
+                    f,
+                    context.span.line + 1 + index,
+                    line_number_size,
+                    &continuation,
+                    line,
+                )?;
+            }
             write!(
                 f,
-                "{indent}{CYAN}{BOLD} | {RESET}{}{colour}{BOLD}{}",
-                " ".repeat(context.span_indent),
-                "^".repeat(context.span.length)
+                "{indent}{CYAN}{BOLD} | {RESET}{continuation}{colour}{BOLD}{}",
+                marker.repeat(display_width(
+                    context.span_lines.last().map_or("", String::as_str),
+                    0,
+                    context.end_length()
+                ))
             )?;
+
             // Write label
             if let Some(label) = &context.label {
                 f.write_char(' ')?;
                 f.write_str(label)?;
             }
             writeln!(f, "{RESET}")?;
-        }
+        } else {
+            // Write span highlighter, with the label of the last span:
+            // `    |      ^^^^^^ ------ first defined here`
+            write!(f, "{indent}{CYAN}{BOLD} | {RESET}")?;
+            let mut labels = Vec::with_capacity(block.len());
+            let mut column = 0;
+            for &(span_context, is_primary) in block {
+                let (colour, marker) = self.marker(is_primary);
+                let span_indent = block_indent(span_context, context);
+                let position = column.max(display_width(span_line, 0, span_indent));
+                let length = display_width(span_line, span_indent, span_context.span.length);
+                write!(
+                    f,
+                    "{}{colour}{BOLD}{}",
+                    " ".repeat(position.saturating_sub(column)),
+                    marker.repeat(length)
+                )?;
+                labels.push((position, colour, span_context.label.as_deref()));
+                column = position + length;
+            }
+            if let Some((_, _, Some(label))) = labels.pop() {
+                f.write_char(' ')?;
+                f.write_str(label)?;
+            }
+            writeln!(f, "{RESET}")?;
 
-        // Write notes
-        if !self.notes.is_empty() {
-            writeln!(f, "{indent}{CYAN}{BOLD} | {RESET}")?;
-            for note in &self.notes {
-                writeln!(f, "{indent}{CYAN}{BOLD} = {DEFAULT}note{RESET}: {note}")?;
+            // Write the labels of the other spans, from right to left:
+            // `    |      |`
+            // `    |      duplicate key here`
+            while let Some((position, colour, label)) = labels.pop() {
+                let Some(label) = label else {
+                    continue;
+                };
+                let connectors: Vec<_> = labels
+                    .iter()
+                    .filter(|(_, _, label)| label.is_some())
+                    .map(|&(position, colour, _)| (position, colour))
+                    .collect();
+
+                write_connectors(
+                    f,
+                    &indent,
+                    &[connectors.as_slice(), &[(position, colour)]].concat(),
+                )?;
+                f.write_char('\n')?;
+                let column = write_connectors(f, &indent, &connectors)?;
+                writeln!(
+                    f,
+                    "{}{colour}{BOLD}{label}{RESET}",
+                    " ".repeat(position.saturating_sub(column))
+                )?;
             }
         }
 
+        // Write source lines following the spans
+        let last_context = block
+            .iter()
+            .map(|&(span_context, _)| span_context)
+            .max_by_key(|span_context| span_context.last_line())
+            .unwrap_or(context);
+        for (index, line) in last_context.lines_after.iter().enumerate() {
+            write_source_line(
+                f,
+                last_context.span.last_line() + 1 + index,
+                line_number_size,
+                margin,
+                line,
+            )?;
+        }
+        *last_written = (*last_written).max(last_context.last_line());
+
         Ok(())
     }
+
+    /// Returns the colour and the character to highlight a span with, primary spans are
+    /// highlighted with `^` in the colour of the severity, related spans with `-`.
+    fn marker(&self, is_primary: bool) -> (&'static str, &'static str) {
+        if is_primary {
+            (self.severity.colour(), "^")
+        } else {
+            (CYAN, "-")
+        }
+    }
 }
 
-impl core::error::Error for Diagnostic {}
+/// Returns a column ruler `width` columns wide, starting at the one-indexed `column`, where every
+/// fifth column is numbered: `....5....10...`
+fn ruler(column: usize, width: usize) -> String {
+    let mut ruler = String::with_capacity(width);
+    let mut column = column;
+
+    while ruler.len() < width {
+        if column.is_multiple_of(5) {
+            let number = column.to_string();
+            column += number.len();
+            ruler.push_str(&number);
+        } else {
+            column += 1;
+            ruler.push('.');
+        }
+    }
+    ruler.truncate(width);
+
+    ruler
+}
+
+/// Returns how many columns wide `length` graphemes of a line are when displayed, starting from the
+/// grapheme `start`. Wide characters are two columns, and graphemes past the end of the line are
+/// one column.
+fn display_width(line: &str, start: usize, length: usize) -> usize {
+    let (count, width) = line
+        .graphemes(true)
+        .skip(start)
+        .take(length)
+        .fold((0, 0), |(count, width), grapheme| {
+            (count + 1, width + grapheme.width())
+        });
+
+    width + length.saturating_sub(count)
+}
+
+/// Group contexts ordered by their spans into the blocks they are displayed in, contexts with
+/// single line spans on the same line share a block.
+fn blocks<'c>(contexts: &[(&'c Context, bool)]) -> Vec<Vec<(&'c Context, bool)>> {
+    let mut blocks: Vec<Vec<(&Context, bool)>> = Vec::new();
+
+    for &(context, is_primary) in contexts {
+        if let Some(block) = blocks.last_mut()
+            && let Some((first, _)) = block.first()
+            && !first.span.is_multiline()
+            && !context.span.is_multiline()
+            && first.span.line == context.span.line
+        {
+            block.push((context, is_primary));
+        } else {
+            blocks.push(vec![(context, is_primary)]);
+        }
+    }
+
+    blocks
+}
+
+/// Returns how indented into the source lines of the first context of a block a context's span
+/// starts.
+fn block_indent(context: &Context, first: &Context) -> usize {
+    context
+        .span
+        .column
+        .saturating_sub(1)
+        .saturating_sub(first.line_start())
+}
+
+/// Write the gutter followed by a `|` in its colour at each position, returning the column after
+/// the last `|`.
+fn write_connectors(
+    f: &mut dyn Write,
+    indent: &str,
+    connectors: &[(usize, &str)],
+) -> Result<usize, core::fmt::Error> {
+    write!(f, "{indent}{CYAN}{BOLD} | {RESET}")?;
+
+    let mut column = 0;
+    for &(position, colour) in connectors {
+        write!(
+            f,
+            "{}{colour}{BOLD}|{RESET}",
+            " ".repeat(position.saturating_sub(column))
+        )?;
+        column = position + 1;
+    }
+
+    Ok(column)
+}
+
+/// Write a line of source with its line number right-aligned in the gutter, followed by the
+/// margin:
+/// `` 99 | some source code here``
+fn write_source_line(
+    f: &mut dyn Write,
+    line_number: usize,
+    line_number_size: usize,
+    margin: &str,
+    line: &str,
+) -> core::fmt::Result {
+    writeln!(
+        f,
+        "{CYAN}{BOLD}{line_number:>line_number_size$} | {RESET}{margin}{line}"
+    )
+}
+
+/// Returns the distinct codes of some diagnostics in the order they first appear, formatted for a
+/// summary line: ` [TS0102, TS0103]`, or an empty string if none of the diagnostics have a code.
+fn codes<'d>(diagnostics: impl Iterator<Item = &'d Diagnostic>) -> String {
+    let mut codes: Vec<&str> = Vec::new();
+    for code in diagnostics.filter_map(|diagnostic| diagnostic.code.as_deref()) {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+
+    if codes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", codes.join(", "))
+    }
+}
+
+/// Returns a count followed by a noun, pluralised if the count is not one: `2 errors`.
+fn pluralize(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -285,9 +1127,22 @@ mod test {
 
     use std::io::{Write, stderr, stdout};
 
-    use alloc::string::ToString;
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+    use core::fmt::Write as _;
 
-    use crate::diagnostic::{Context, Diagnostic, Diagnostics, Span};
+    use ts_ansi::{
+        StripStyles,
+        style::{BOLD, CYAN, DEFAULT, RED, RESET},
+    };
+
+    use crate::diagnostic::{
+        Context, Diagnostic, Diagnostics, DisplayStyle, FinalizeOptions, Severity, Span,
+    };
 
     const SOURCE: &str = r#"use alloc::boxed::Box;
 use core::{error::Error, fmt};
@@ -335,6 +1190,13 @@ impl fmt::Display for Report<'_> {
 
     const MINIFIED_SOURCE: &str = r#"async function Ui(n){return location.href=n,await mu()}function mu(){let n=t=>{setTimeout(()=>n(t),400)};return new Promise(n)}var br=class{element;contents;action;constructor(t,e){this.element=ht(`${t}/error`,HTMLElement),this.contents=ht(`${t}/error/content`,HTMLElement),this.action=e}clearError(){this.element.classList.add("collapse"),this.element.ariaHidden="true",this.contents.textContent=""}addError(t){if(this.contents.textContent===""){this.element.classList.remove("collapse"),this.element.ariaHidden="false",this.contents.textContent=`Could not ${this.action}: ${t}`;return}this.contents.textContent+=`, ${t}`}setSomethingWentWrong(){this.element.classList.remove("collapse"),this.element.ariaHidden="false",this.contents.textContent=`Something went wrong while trying to ${this.action}. Try again later.`}},Nr=class{input;error;constructor(t,e){this.input=ht(`${t}${e}/input`,HTMLInputElement),this.error=ht(`${t}${e}/error`,HTMLElement),this.input.addEventListener("input",()=>{this.input.setCustomValidity("")})}getValue(){return this.input.type==="checkbox"?this.input.checked?"checked":"unchecked":this.input.value}setLock(t){this.input.disabled=t}clearError(){this.input.setCustomValidity(""),this.error.classList.add("hidden"),this.error.ariaHidden="true",this.error.textContent="!"}addError(t){if(this.error.textContent==="!"){this.input.setCustomValidity(t),this.error.classList.remove("hidden"),this.error.ariaHidden="false",this.error.textContent=`Invalid value: ${t}`;return}this.error.textContent+=`, ${t}`,this.input.setCustomValidity(this.error.textContent??"Invalid value")}},ge=class{form;formError;submitButton;inputs;constructor(t,e,r){this.form=ht(t,HTMLFormElement),this.formError=new br(t,r),this.submitButton=ht(`${t}/submit`,HTMLButtonElement);let o=new Map;for(let i of e)o.set(i,new Nr(t,i));this.inputs=o}clearErrors(){this.formError.clearError();for(let t of this.inputs.values())t.clearError()}setLock(t){this.submitButton.disabled=t;for(let e of this.inputs.values())e.setLock(t)}setInputErrors(t){if(!t||t.length===0){this.formError.addError("an unknown field is invalid");return}for(let e of t){let r=this.inputs.get(e.pointer)??null;r?r.addError(e.detail):this.formError.addError(`field ${e.pointer} ${e.detail}`)}}getValues(){let t=new Map;for(let[e,r]of this.inputs)t.set(e,r.getValue());return t}};"#;
 
+    fn strip_styles(output: impl core::fmt::Display) -> String {
+        let mut plain = String::new();
+        write!(StripStyles::new(&mut plain), "{output}")
+            .expect("writing to a string should succeed");
+        plain
+    }
+
     #[test]
     fn show_output() {
         let _stdout = stdout().lock();
@@ -396,4 +1258,628 @@ impl fmt::Display for Report<'_> {
 
         stderr.flush().expect("flusing stderr should not fail");
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn round_trips_serde() {
+        let diagnostic = Diagnostic::error("struct `Report` is never used")
+            .file_path("crates/ts-error/src/report.rs")
+            .context(
+                Context::new(SOURCE, Span::default().line(7).column(12).length(6))
+                    .label("this is unused"),
+            )
+            .add_note("`#[warn(dead_code)]` on by default");
+
+        let json = serde_json::to_string(&diagnostic).expect("serialization should succeed");
+        let round_tripped: Diagnostic =
+            serde_json::from_str(&json).expect("deserialization should succeed");
+
+        assert_eq!(diagnostic.to_string(), round_tripped.to_string());
+        assert_eq!(diagnostic.notes, round_tripped.notes);
+        let context = round_tripped.context.expect("context should round trip");
+        assert_eq!(Span::default().line(7).column(12).length(6), context.span);
+        assert_eq!(Some("this is unused"), context.label.as_deref());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serializes_to_json() {
+        let mut diagnostics = Diagnostics::new("linting");
+        diagnostics.push(
+            Diagnostic::warning("struct `Report` is never used")
+                .file_path("crates/ts-error/src/report.rs")
+                .context(Context::new(
+                    SOURCE,
+                    Span::default().line(7).column(12).length(6),
+                ))
+                .add_note("`#[warn(dead_code)]` on by default"),
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&diagnostics.to_json().expect("serialization should succeed"))
+                .expect("output should be JSON");
+        let field = |pointer: &str| json.pointer(pointer).cloned().unwrap_or_default();
+
+        assert_eq!("linting", field("/context"));
+        assert_eq!("Warning", field("/problems/0/severity"));
+        assert_eq!(
+            "struct `Report` is never used",
+            field("/problems/0/headline")
+        );
+        assert_eq!(
+            "crates/ts-error/src/report.rs",
+            field("/problems/0/file_path")
+        );
+        assert_eq!(7, field("/problems/0/context/span/line"));
+        assert_eq!(12, field("/problems/0/context/span/column"));
+        assert_eq!(6, field("/problems/0/context/span/length"));
+        assert_eq!(
+            "`#[warn(dead_code)]` on by default",
+            field("/problems/0/notes/0")
+        );
+
+        let output = diagnostics.render(DisplayStyle::Plain).to_string();
+        assert!(
+            output.contains("crates/ts-error/src/report.rs:7:12"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn numbers_lines_around_span() {
+        let context = Context::new(SOURCE, Span::default().line(11).column(1).length(1))
+            .lines_after(SOURCE, 2);
+
+        let output = Diagnostic::error("some headline here")
+            .context(context)
+            .render(DisplayStyle::Color)
+            .to_string();
+
+        for (line_number, line) in [
+            (" 9", "    pub source: Box<dyn Error + 'e>,"),
+            ("10", "}"),
+            ("11", "impl<'e> Report<'e> {"),
+            ("12", "    /// Create a new error report."),
+            ("13", "    pub fn new<E: Error + 'e>(source: E) -> Self {"),
+        ] {
+            assert!(output.contains(&format!("{CYAN}{BOLD}{line_number} | {RESET}{line}\n")));
+        }
+    }
+
+    #[test]
+    fn underlines_multiline_span() {
+        let source = "{\n  \"a\": [1,\n    2]\n}";
+        let context = Context::new(source, Span::default().line(2).column(8).end(3, 6))
+            .label("some label here");
+
+        let output = Diagnostic::error("some headline here")
+            .context(context)
+            .render(DisplayStyle::Color)
+            .to_string();
+
+        let gutter = format!(" {CYAN}{BOLD} | {RESET}");
+        let continuation = format!("{RED}{BOLD}|{RESET} ");
+        for line in [
+            format!("{CYAN}{BOLD}1 | {RESET}  {{"),
+            format!("{CYAN}{BOLD}2 | {RESET}    \"a\": [1,"),
+            format!("{gutter}         {RED}{BOLD}^^^{RESET}"),
+            format!("{CYAN}{BOLD}3 | {RESET}{continuation}    2]"),
+            format!("{gutter}{continuation}{RED}{BOLD}^^^^^^ some label here{RESET}"),
+        ] {
+            assert!(
+                output.contains(&format!("{line}\n")),
+                "missing `{line}` in {output:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn aligns_highlight_after_wide_graphemes() {
+        let source = "let crab = \"🦀\"; let key = \"다람쥐\";";
+        let context = Context::new(source, Span::default().line(1).column(27).length(5))
+            .label("some label here");
+
+        let output = Diagnostic::error("some headline here")
+            .context(context)
+            .render(DisplayStyle::Plain)
+            .to_string();
+
+        let line = format!("  | {}^^^^^^^^ some label here\n", " ".repeat(27));
+        assert!(output.contains(&line), "missing `{line}` in {output:?}");
+    }
+
+    #[test]
+    fn aligns_gutter_of_multiline_span() {
+        let source = "1\n2\n3\n4\n5\n6\n7\n{\n  \"a\": [\n    1,\n    2]\n}";
+        let context = Context::new(source, Span::default().line(9).column(8).end(11, 6))
+            .label("some label here");
+
+        let output = Diagnostic::error("some headline here")
+            .context(context)
+            .render(DisplayStyle::Plain)
+            .to_string();
+
+        let expected = [
+            "error: some headline here",
+            "  --> line 9, column 8",
+            "   | ",
+            " 7 |   7",
+            " 8 |   {",
+            " 9 |     \"a\": [",
+            "   |          ^",
+            "10 | |     1,",
+            "11 | |     2]",
+            "   | | ^^^^^^ some label here",
+        ];
+        assert_eq!(expected.to_vec(), output.lines().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn extends_diagnostics() {
+        let mut diagnostics = Diagnostics::new("test");
+        diagnostics.push(Diagnostic::error("some error"));
+
+        let mut other = Diagnostics::new("other");
+        other.push(Diagnostic::warning("some warning"));
+        other.push(Diagnostic::error("another error"));
+
+        assert!(!diagnostics.has_warnings());
+
+        diagnostics.extend(other);
+        assert_eq!(2, diagnostics.errors().count());
+        assert_eq!(1, diagnostics.warnings().count());
+        assert!(diagnostics.has_errors());
+        assert!(diagnostics.has_warnings());
+        assert_eq!("test", diagnostics.context);
+    }
+
+    #[test]
+    fn wraps_single_diagnostic() {
+        let diagnostics = Diagnostics::from_single("test", Diagnostic::error("some error"));
+        assert_eq!("test", diagnostics.context);
+        assert_eq!(1, diagnostics.problems.len());
+        assert_eq!(
+            Some("some error"),
+            diagnostics
+                .problems
+                .first()
+                .map(|problem| problem.headline.as_str())
+        );
+
+        let diagnostics = Diagnostic::warning("some warning").into_diagnostics("other");
+        assert_eq!("other", diagnostics.context);
+        assert_eq!(1, diagnostics.warnings().count());
+    }
+
+    #[test]
+    fn displays_without_summary() {
+        let mut diagnostics = Diagnostics::new("test");
+        diagnostics.push(Diagnostic::error("some error"));
+        diagnostics.push(Diagnostic::warning("some warning"));
+
+        let output = diagnostics.display_without_summary().to_string();
+        assert!(output.contains("some error"));
+        assert!(output.contains("some warning"));
+        assert!(!output.contains("generated"));
+
+        assert!(diagnostics.to_string().contains("test generated 1 errors"));
+    }
+
+    #[test]
+    fn counts_info_and_help_separately() {
+        let mut diagnostics = Diagnostics::new("test");
+        diagnostics.push(Diagnostic::help("consider doing X").file_path("file.json"));
+        diagnostics.push(Diagnostic::error("some error").file_path("file.json"));
+        diagnostics.push(Diagnostic::help("consider doing Y").file_path("file.json"));
+        diagnostics.push(Diagnostic::info("some info").file_path("file.json"));
+
+        assert_eq!(1, diagnostics.errors().count());
+        assert_eq!(0, diagnostics.warnings().count());
+        assert_eq!(2, diagnostics.helps().count());
+        assert!(diagnostics.has_errors());
+
+        let output = diagnostics.render(DisplayStyle::Plain).to_string();
+        let grouped = strip_styles(diagnostics.display_grouped_by_file());
+
+        assert!(output.contains("help: consider doing X"), "{output}");
+        assert!(
+            output.contains("error: test generated 1 errors"),
+            "{output}"
+        );
+        assert!(
+            output.contains("info: test generated 1 info message\n"),
+            "{output}"
+        );
+        assert!(
+            output.contains("help: test generated 2 help messages"),
+            "{output}"
+        );
+        assert!(
+            grouped.starts_with("file.json: 1 error, 1 info message, 2 help messages\n"),
+            "{grouped}"
+        );
+    }
+
+    #[test]
+    fn lists_spans() {
+        let mut diagnostics = Diagnostics::new("test");
+        diagnostics.push(
+            Diagnostic::error("first").context(Context::new(SOURCE, Span::default().line(7))),
+        );
+        diagnostics.push(Diagnostic::warning("contextless"));
+        diagnostics.push(Diagnostic::warning("second").context(Context::new(
+            SOURCE,
+            Span::default().line(13).column(5).length(3),
+        )));
+
+        let spans: Vec<_> = diagnostics
+            .spans()
+            .map(|(problem, span)| (problem.headline.as_str(), span))
+            .collect();
+        assert_eq!(
+            vec![
+                ("first", Span::default().line(7)),
+                ("second", Span::default().line(13).column(5).length(3)),
+            ],
+            spans
+        );
+    }
+
+    #[test]
+    fn displays_code() {
+        let diagnostic = Diagnostic::error("some headline here")
+            .code("MY001")
+            .file_path("some/path/to/a.file")
+            .context(Context::new(
+                SOURCE,
+                Span::default().line(7).column(12).length(6),
+            ));
+
+        assert!(diagnostic.to_string().contains("error[MY001]"));
+        assert_eq!(
+            "some/path/to/a.file:7:12: error[MY001]: some headline here",
+            diagnostic.oneline()
+        );
+
+        let diagnostic = Diagnostic::warning("some headline here");
+        assert_eq!("warning: some headline here", diagnostic.oneline());
+
+        let mut diagnostics = Diagnostics::new("linting");
+        diagnostics.push(Diagnostic::error("first").code("TS0102"));
+        diagnostics.push(Diagnostic::error("second"));
+        diagnostics.push(Diagnostic::error("third").code("TS0001"));
+        diagnostics.push(Diagnostic::error("fourth").code("TS0102"));
+        diagnostics.push(Diagnostic::warning("fifth"));
+
+        let output = diagnostics.render(DisplayStyle::Plain).to_string();
+
+        assert!(
+            output.contains("error: linting generated 4 errors [TS0102, TS0001]\n"),
+            "{output}"
+        );
+        assert!(
+            output.contains("warning: linting generated 1 warnings\n"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn wraps_notes() {
+        let output = Diagnostic::error("some headline here")
+            .add_note(format!(
+                "a {BOLD}long{RESET} note that describes the expected schema of the value in detail"
+            ))
+            .wrapped(40)
+            .render(DisplayStyle::Color)
+            .to_string();
+
+        let visible = output
+            .replace(BOLD, "")
+            .replace(CYAN, "")
+            .replace(DEFAULT, "")
+            .replace(RED, "")
+            .replace(RESET, "");
+        let notes: Vec<_> = visible
+            .lines()
+            .skip_while(|line| !line.contains("= note:"))
+            .collect();
+        assert_eq!(
+            vec![
+                "  = note: a long note that describes the",
+                "          expected schema of the value",
+                "          in detail",
+            ],
+            notes
+        );
+        assert!(output.contains(&format!("a {BOLD}long{RESET} note")));
+    }
+
+    #[test]
+    fn writes_plain() {
+        let diagnostic = || {
+            Diagnostic::error("unexpected token")
+                .code("TS0102")
+                .file_path("file.json")
+                .context(Context::new(
+                    SOURCE,
+                    Span::default().line(7).column(9).length(4),
+                ))
+                .add_note("expected a value")
+        };
+        let mut diagnostics = Diagnostics::new("test");
+        diagnostics.push(diagnostic());
+        let diagnostic = diagnostic();
+
+        let mut plain = String::new();
+        diagnostic
+            .write_plain(&mut plain)
+            .expect("writing to a string should succeed");
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.starts_with("error[TS0102]: unexpected token\n"));
+
+        // The plain output is the coloured output without the escapes.
+        let mut stripped = String::new();
+        write!(
+            StripStyles::new(&mut stripped),
+            "{}",
+            diagnostic.render(DisplayStyle::Color)
+        )
+        .expect("writing to a string should succeed");
+        assert_eq!(stripped, plain);
+        assert_eq!(plain, diagnostic.render(DisplayStyle::Plain).to_string());
+
+        let mut plain = String::new();
+        diagnostics
+            .write_plain(&mut plain)
+            .expect("writing to a string should succeed");
+        let mut stripped = String::new();
+        write!(StripStyles::new(&mut stripped), "{diagnostics}")
+            .expect("writing to a string should succeed");
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(stripped, plain);
+    }
+
+    #[test]
+    fn finalizes_diagnostics() {
+        let diagnostic = |severity, headline: &str, line: usize| {
+            Diagnostic::new(severity, headline)
+                .file_path("file.json")
+                .context(Context::new(SOURCE, Span::default().line(line)))
+        };
+
+        let messy = || {
+            let mut diagnostics = Diagnostics::new("test");
+            diagnostics.push(diagnostic(Severity::Warning, "unused", 7));
+            diagnostics.push(diagnostic(Severity::Error, "invalid", 13));
+            diagnostics.push(diagnostic(Severity::Warning, "unused", 7));
+            diagnostics.push(diagnostic(Severity::Error, "invalid", 2));
+            diagnostics.push(diagnostic(Severity::Error, "invalid", 13));
+            diagnostics.push(diagnostic(Severity::Warning, "unused", 9));
+            diagnostics
+        };
+        let locations = |diagnostics: &Diagnostics| -> Vec<(Severity, usize)> {
+            diagnostics
+                .spans()
+                .map(|(problem, span)| (problem.severity, span.line))
+                .collect()
+        };
+
+        let finalized = messy().finalize(FinalizeOptions::default().sort().dedup().cap(3));
+        assert_eq!(
+            vec![
+                (Severity::Error, 2),
+                (Severity::Error, 13),
+                (Severity::Warning, 7)
+            ],
+            locations(&finalized)
+        );
+
+        let finalized = messy().finalize(FinalizeOptions::default().dedup());
+        assert_eq!(
+            vec![
+                (Severity::Warning, 7),
+                (Severity::Error, 13),
+                (Severity::Error, 2),
+                (Severity::Warning, 9)
+            ],
+            locations(&finalized)
+        );
+
+        assert_eq!(
+            6,
+            messy().finalize(FinalizeOptions::default()).problems.len()
+        );
+    }
+
+    #[test]
+    fn sorts_by_severity_then_location() {
+        let mut diagnostics = Diagnostics::new("test");
+        diagnostics.push(Diagnostic::help("some help"));
+        diagnostics.push(Diagnostic::info("some info"));
+        diagnostics.push(Diagnostic::warning("contextless warning"));
+        diagnostics.push(
+            Diagnostic::warning("warning 7:1")
+                .context(Context::new(SOURCE, Span::default().line(7))),
+        );
+        diagnostics.push(Diagnostic::error("first contextless error"));
+        diagnostics.push(
+            Diagnostic::error("error 13:5")
+                .context(Context::new(SOURCE, Span::default().line(13).column(5))),
+        );
+        diagnostics.push(
+            Diagnostic::warning("warning 2:3")
+                .context(Context::new(SOURCE, Span::default().line(2).column(3))),
+        );
+        diagnostics.push(Diagnostic::error("second contextless error"));
+        diagnostics.push(
+            Diagnostic::error("error 13:2")
+                .context(Context::new(SOURCE, Span::default().line(13).column(2))),
+        );
+        diagnostics.push(
+            Diagnostic::error("error 7:1").context(Context::new(SOURCE, Span::default().line(7))),
+        );
+
+        diagnostics.sort();
+
+        let headlines: Vec<_> = diagnostics
+            .problems
+            .iter()
+            .map(|problem| problem.headline.as_str())
+            .collect();
+        assert_eq!(
+            vec![
+                "error 7:1",
+                "error 13:2",
+                "error 13:5",
+                "first contextless error",
+                "second contextless error",
+                "warning 2:3",
+                "warning 7:1",
+                "contextless warning",
+                "some info",
+                "some help",
+            ],
+            headlines
+        );
+    }
+
+    #[test]
+    fn summarises_without_context() {
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.push(Diagnostic::error("some error"));
+        diagnostics.push(Diagnostic::error("another error"));
+
+        let output = diagnostics.render(DisplayStyle::Color).to_string();
+        assert!(output.ends_with(&format!(":{RESET} generated 2 errors\n")));
+
+        let diagnostics = diagnostics.with_context("test");
+        assert!(diagnostics.to_string().contains("test generated 2 errors"));
+    }
+
+    #[test]
+    fn groups_by_file() {
+        let mut diagnostics = Diagnostics::new("test");
+        diagnostics.push(Diagnostic::error("first error").file_path("file.json"));
+        diagnostics.push(Diagnostic::warning("some warning").file_path("file.json"));
+        diagnostics.push(Diagnostic::error("contextless error"));
+        diagnostics.push(Diagnostic::warning("other warning").file_path("other.json"));
+        diagnostics.push(Diagnostic::error("second error").file_path("file.json"));
+
+        let output = diagnostics.display_grouped_by_file().to_string();
+        let headers: Vec<_> = output
+            .lines()
+            .map(|line| line.replace(BOLD, "").replace(RESET, ""))
+            .filter(|line| line.contains(".json: "))
+            .collect();
+        assert_eq!(
+            vec!["file.json: 2 errors, 1 warning", "other.json: 1 warning"],
+            headers
+        );
+
+        let position = |text: &str| output.find(text).expect("output should contain text");
+        assert!(position("first error") < position("second error"));
+        assert!(position("second error") < position("some warning"));
+        assert!(position("some warning") < position("other.json"));
+        assert!(position("other warning") < position("contextless error"));
+    }
+
+    #[test]
+    fn merges_notes_without_duplicates() {
+        let mut diagnostic = Diagnostic::error("some headline here").add_note("the port");
+        diagnostic.merge_notes_from(["the port", "example: 80", "the port", "example: 80"]);
+        diagnostic.merge_notes_from(["help: use a port above 1024"]);
+
+        assert_eq!(
+            vec!["the port", "example: 80", "help: use a port above 1024"],
+            diagnostic.notes
+        );
+    }
+
+    #[test]
+    fn sets_context_at_span() {
+        let span = Span::default().line(7).column(12).length(6);
+
+        assert_eq!(
+            Diagnostic::error("some headline here")
+                .context(Context::new(SOURCE, span))
+                .to_string(),
+            Diagnostic::error("some headline here")
+                .at(SOURCE, span)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn displays_column_ruler() {
+        use crate::diagnostic::ruler;
+
+        assert_eq!("....5....10...15", ruler(1, 16));
+        assert_eq!("..10..", ruler(8, 6));
+
+        let span = Span::default().line(7).column(12).length(6);
+        let diagnostic = Diagnostic::error("some headline here").at(SOURCE, span);
+        let output = strip_styles(diagnostic.display_debug());
+        let plain = diagnostic.render(DisplayStyle::Plain).to_string();
+
+        assert!(!plain.contains("....5"));
+        let lines: Vec<&str> = output.lines().collect();
+        let ruler_line = lines
+            .iter()
+            .find(|line| line.contains("....5....10"))
+            .expect("the ruler should be displayed");
+        let source_line = lines
+            .iter()
+            .find(|line| line.starts_with("7 | "))
+            .expect("the source line should be displayed");
+        let highlight = lines
+            .iter()
+            .find(|line| line.contains('^'))
+            .expect("the span should be highlighted");
+
+        // The ruler starts after the gutter, and column 12 of the ruler is above the span.
+        assert!(ruler_line.starts_with("  | ....5....10"));
+        assert_eq!(source_line.find("| "), ruler_line.find("| "));
+        assert_eq!(
+            ruler_line.find("..15").map(|index| index - 1),
+            highlight.find('^')
+        );
+    }
+
+    #[test]
+    fn displays_related_contexts() {
+        let output = Diagnostic::error("duplicate definition of `Report`")
+            .file_path("crates/ts-error/src/report.rs")
+            .context(
+                Context::new(SOURCE, Span::default().line(7).column(12).length(6))
+                    .label("redefined here"),
+            )
+            .related(
+                Context::new(SOURCE, Span::default().line(7).column(1).length(3))
+                    .label("made public here"),
+            )
+            .related(
+                Context::new(SOURCE, Span::default().line(11).column(9).length(6))
+                    .label("first defined here"),
+            )
+            .render(DisplayStyle::Plain)
+            .to_string();
+
+        let expected = [
+            "error: duplicate definition of `Report`",
+            "  --> crates/ts-error/src/report.rs:7:12",
+            "   | ",
+            " 5 | ",
+            " 6 | /// An error report, displays the error stack of some error.",
+            " 7 | pub struct Report<'e> {",
+            "   | ---        ^^^^^^ redefined here",
+            "   | |",
+            "   | made public here",
+            "...",
+            " 9 |     pub source: Box<dyn Error + 'e>,",
+            "10 | }",
+            "11 | impl<'e> Report<'e> {",
+            "   |         ------ first defined here",
+        ];
+        assert_eq!(expected.to_vec(), output.lines().collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,93 @@
+//! An index of the lines in a source.
+
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+/// An index of where each line in a source starts, built once so many contexts can be created over
+/// the same source without splitting it into lines each time.
+///
+/// Lines are split the same way as [`str::lines`].
+pub struct SourceIndex<'a> {
+    /// The source that is indexed.
+    source: &'a str,
+    /// The byte offset each line starts at.
+    line_starts: Vec<usize>,
+}
+impl<'a> SourceIndex<'a> {
+    /// Index the lines of a source.
+    pub fn new(source: &'a str) -> Self {
+        let mut line_starts = Vec::new();
+        if !source.is_empty() {
+            line_starts.push(0);
+        }
+
+        line_starts.extend(
+            source
+                .match_indices('\n')
+                .map(|(offset, _)| offset + 1)
+                .filter(|&start| start < source.len()),
+        );
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// Returns the zero-indexed line of the source, without its line ending.
+    pub fn line(&self, index: usize) -> Option<&'a str> {
+        let start = *self.line_starts.get(index)?;
+        let (end, terminated) = match self.line_starts.get(index + 1) {
+            Some(next) => (next - 1, true),
+            None if self.source.ends_with('\n') => (self.source.len() - 1, true),
+            None => (self.source.len(), false),
+        };
+
+        let line = self.source.get(start..end)?;
+        if terminated {
+            Some(line.strip_suffix('\r').unwrap_or(line))
+        } else {
+            Some(line)
+        }
+    }
+
+    /// Returns the number of lines in the source.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the source that is indexed.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::diagnostic::SourceIndex;
+
+    #[test]
+    fn matches_lines() {
+        let sources = [
+            "",
+            "\n",
+            "one",
+            "one\n",
+            "one\ntwo",
+            "one\r\ntwo\r\n",
+            "one\n\n\nfour\r",
+            "\r\n\r\n",
+        ];
+
+        for source in sources {
+            let index = SourceIndex::new(source);
+            let lines: Vec<&str> = (0..index.line_count())
+                .filter_map(|line| index.line(line))
+                .collect();
+
+            assert_eq!(source.lines().collect::<Vec<_>>(), lines, "{source:?}");
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! The span of some context.
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-/// A span for diagnostics, maps to a location in a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A span for diagnostics, maps to a location in a source file. Spans are ordered by their
+/// location, comparing their line, then column, then length, then end.
 pub struct Span {
     /// One-indexed line number.
     pub line: usize,
@@ -9,6 +11,9 @@ pub struct Span {
     pub column: usize,
     /// Number of graphemes the span goes for.
     pub length: usize,
+    /// One-indexed line and column of the last grapheme of a span that covers multiple lines.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub end: Option<(usize, usize)>,
 }
 impl Default for Span {
     fn default() -> Self {
@@ -16,10 +21,28 @@ impl Default for Span {
             line: 1,
             column: 1,
             length: 1,
+            end: None,
         }
     }
 }
 impl Span {
+    /// Create a span of length one at a one-indexed line and column.
+    ///
+    /// ```
+    /// # use ts_error::diagnostic::Span;
+    /// assert_eq!(
+    ///     Span::default().line(7).column(12).length(6),
+    ///     Span::at(7, 12).length(6)
+    /// );
+    /// ```
+    pub fn at(line: usize, column: usize) -> Self {
+        Self {
+            line,
+            column,
+            ..Default::default()
+        }
+    }
+
     /// Sets the line of the span, lines should be one-indexed.
     pub fn line(mut self, line: usize) -> Self {
         self.line = line;
@@ -37,4 +60,148 @@ impl Span {
         self.length = length;
         self
     }
+
+    /// Sets the one-indexed line and column of the last grapheme of the span, for spans that cover
+    /// multiple lines.
+    pub fn end(mut self, line: usize, column: usize) -> Self {
+        self.end = Some((line, column));
+        self
+    }
+
+    /// Returns if the span covers multiple lines.
+    pub fn is_multiline(&self) -> bool {
+        self.end.is_some_and(|(line, _)| line > self.line)
+    }
+
+    /// Returns the one-indexed line the span ends on.
+    pub fn last_line(&self) -> usize {
+        match self.end {
+            Some((line, _)) => line.max(self.line),
+            None => self.line,
+        }
+    }
+
+    /// Relocate the span by some number of lines and columns, saturating at the first line and
+    /// column.
+    ///
+    /// The end of a span that covers multiple lines is only moved by `line_delta`, as the columns
+    /// of the line it ends on are unaffected by changes to the line the span starts on.
+    pub fn shift(mut self, line_delta: isize, column_delta: isize) -> Self {
+        self.line = self.line.saturating_add_signed(line_delta).max(1);
+        self.column = self.column.saturating_add_signed(column_delta).max(1);
+        self.end = self
+            .end
+            .map(|(line, column)| (line.saturating_add_signed(line_delta).max(1), column));
+        self
+    }
+
+    /// Advance the span past a character. A newline moves the span to the start of the next line,
+    /// a carriage return does not move the span, and any other character advances the column by
+    /// one.
+    ///
+    /// Graphemes made of multiple characters should use [`Self::advance_columns`].
+    pub fn advance(&mut self, character: char) {
+        match character {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            '\r' => {}
+            _ => self.column += 1,
+        }
+    }
+
+    /// Advance the column of the span.
+    pub fn advance_columns(&mut self, columns: usize) {
+        self.column += columns;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use crate::diagnostic::Span;
+
+    #[test]
+    fn advances_across_newlines() {
+        let mut span = Span::default();
+        for character in "ab\r\ncd\n\ne".chars() {
+            span.advance(character);
+        }
+        assert_eq!(Span::default().line(4).column(2), span);
+    }
+
+    #[test]
+    fn handles_multiline() {
+        let span = Span::default().line(2).column(5);
+        assert!(!span.is_multiline());
+        assert_eq!(2, span.last_line());
+
+        let span = span.end(4, 1);
+        assert!(span.is_multiline());
+        assert_eq!(4, span.last_line());
+
+        let span = span.end(2, 8);
+        assert!(!span.is_multiline());
+        assert_eq!(2, span.last_line());
+    }
+
+    #[test]
+    fn shifts() {
+        let span = Span::default().line(3).column(5).length(4);
+        assert_eq!(
+            Span::default().line(4).column(3).length(4),
+            span.shift(1, -2)
+        );
+        assert_eq!(Span::default().length(4), span.shift(-10, -10));
+
+        let span = Span::default().line(3).column(5).end(6, 2);
+        assert_eq!(
+            Span::default().line(4).column(3).end(7, 2),
+            span.shift(1, -2)
+        );
+    }
+
+    #[test]
+    fn advances_across_graphemes() {
+        // Multi-byte characters are a single column.
+        let mut span = Span::default();
+        for character in "다람쥐".chars() {
+            span.advance(character);
+        }
+        assert_eq!(Span::default().column(4), span);
+
+        span.advance('\n');
+        span.advance_columns(2);
+        assert_eq!(Span::default().line(2).column(3), span);
+    }
+
+    #[test]
+    fn orders_by_position() {
+        let mut spans = vec![
+            Span::default().line(3),
+            Span::default().line(2).column(4).length(2),
+            Span::default().line(2).column(4),
+            Span::default().line(2).column(1).length(8),
+            Span::default().line(1).column(9),
+        ];
+        spans.sort();
+
+        assert_eq!(
+            vec![
+                Span::default().line(1).column(9),
+                Span::default().line(2).column(1).length(8),
+                Span::default().line(2).column(4),
+                Span::default().line(2).column(4).length(2),
+                Span::default().line(3),
+            ],
+            spans
+        );
+        assert!(Span::default().line(2).column(4) < Span::default().line(2).column(5));
+        assert_eq!(
+            core::cmp::Ordering::Equal,
+            Span::default().cmp(&Span::default())
+        );
+    }
 }
@@ -0,0 +1,69 @@
+//! Longer explanations of diagnostic codes, like `rustc --explain`.
+
+use alloc::collections::BTreeMap;
+use std::sync::{PoisonError, RwLock};
+
+/// The explanation of each registered diagnostic code.
+static EXPLANATIONS: RwLock<BTreeMap<&'static str, &'static str>> = RwLock::new(BTreeMap::new());
+
+/// Register the explanation of a diagnostic code, replacing any explanation the code already has.
+///
+/// Tools should register their codes at startup, so they can be explained by [`explain`].
+pub fn register_explanation(code: &'static str, explanation: &'static str) {
+    register_explanations(&[(code, explanation)]);
+}
+
+/// Register the explanations of many diagnostic codes, see [`register_explanation`].
+pub fn register_explanations(explanations: &[(&'static str, &'static str)]) {
+    let mut registry = EXPLANATIONS.write().unwrap_or_else(PoisonError::into_inner);
+
+    for &(code, explanation) in explanations {
+        registry.insert(code, explanation);
+    }
+}
+
+/// Returns the explanation of a diagnostic code, if one has been registered.
+///
+/// ## Usage
+/// An `--explain <CODE>` flag for a CLI:
+/// ```
+/// use ts_error::{explain, register_explanations};
+///
+/// register_explanations(&[("TS0102", "A key was defined more than once in the same object.")]);
+///
+/// let code = std::env::args().skip_while(|arg| arg != "--explain").nth(1);
+/// if let Some(code) = code {
+///     match explain(&code) {
+///         Some(explanation) => println!("{explanation}"),
+///         None => eprintln!("`{code}` is not a known diagnostic code"),
+///     }
+/// }
+/// ```
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(code)
+        .copied()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{explain, register_explanation, register_explanations};
+
+    #[test]
+    fn explains_registered_codes() {
+        register_explanation("TEST0001", "Some explanation.");
+        register_explanations(&[
+            ("TEST0002", "Another explanation."),
+            ("TEST0003", "A third explanation."),
+        ]);
+
+        assert_eq!(Some("Some explanation."), explain("TEST0001"));
+        assert_eq!(Some("A third explanation."), explain("TEST0003"));
+        assert_eq!(None, explain("TEST9999"));
+
+        register_explanation("TEST0001", "A replaced explanation.");
+        assert_eq!(Some("A replaced explanation."), explain("TEST0001"));
+    }
+}
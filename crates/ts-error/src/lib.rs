@@ -9,6 +9,8 @@ extern crate alloc;
 extern crate std;
 
 pub mod diagnostic;
+#[cfg(feature = "std")]
+mod explain;
 mod logger;
 mod program_exit;
 mod report;
@@ -18,14 +20,17 @@ use alloc::string::{String, ToString};
 #[cfg(feature = "log")]
 pub use logger::LogError;
 pub use program_exit::{ProgramReport, ReportProgramExit};
-pub use report::{IntoReport, Report};
+pub use report::{IntoReport, IntoReportDisplay, Report};
 
+#[cfg(feature = "std")]
+pub use explain::{explain, register_explanation, register_explanations};
 #[cfg(feature = "std")]
 pub use logger::StderrError;
 
 /// Normalize an error message.
-/// * Starts with lowercase character unless followed by an uppercase character.
-/// * Does not end with any punctuation.
+/// * Starts with lowercase character unless followed by an uppercase character, messages starting
+///   with a code span in backticks keep their case.
+/// * Does not end with any punctuation, punctuation inside a closing code span is kept.
 pub fn normalize_message<S: ToString>(message: S) -> String {
     let message = message.to_string();
     let message = message.trim();
@@ -53,8 +58,11 @@ pub fn normalize_message<S: ToString>(message: S) -> String {
     }
 
     let mut chars = chars.rev().peekable();
-    // Skip trailing punctuation
-    while chars.next_if(char::is_ascii_punctuation).is_some() {}
+    // Skip trailing punctuation, stopping at the end of a code span
+    while chars
+        .next_if(|character| character.is_ascii_punctuation() && *character != '`')
+        .is_some()
+    {}
 
     output.push_str(&chars.rev().collect::<String>());
 
@@ -91,4 +99,16 @@ mod test {
         let message = "  message .,;/  ";
         assert_eq!("message", normalize_message(message));
     }
+
+    #[test]
+    fn preserves_code_spans() {
+        let message = "`Foo` is unused";
+        assert_eq!(message, normalize_message(message));
+
+        let message = "expected `x;`";
+        assert_eq!(message, normalize_message(message));
+
+        let message = "expected `x;`.";
+        assert_eq!("expected `x;`", normalize_message(message));
+    }
 }
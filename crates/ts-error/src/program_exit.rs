@@ -3,7 +3,7 @@
 use alloc::boxed::Box;
 use core::{error::Error, fmt};
 
-use crate::Report;
+use crate::{Report, diagnostic::Diagnostics, report::MAX_DEPTH};
 
 /// Type alias for a program that reports it's exit.
 pub type ReportProgramExit = Result<(), ProgramReport>;
@@ -15,6 +15,13 @@ impl<E: Error + 'static> From<E> for ProgramReport {
         Self(Box::new(value))
     }
 }
+impl ProgramReport {
+    /// Create a program report from a collection of diagnostics, the report will display every
+    /// diagnostic.
+    pub fn from_diagnostics(diagnostics: Diagnostics) -> Self {
+        Self(Box::new(diagnostics))
+    }
+}
 impl fmt::Debug for ProgramReport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{self}")
@@ -22,7 +29,8 @@ impl fmt::Debug for ProgramReport {
 }
 impl fmt::Display for ProgramReport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let report = Report::new(self.0.as_ref());
+        // Diagnostics are displayed in full after the report, rather than as part of it.
+        let report = Report::new(self.0.as_ref()).skip_diagnostics();
 
         #[cfg(feature = "std")]
         let current_exe = std::env::current_exe().ok();
@@ -38,6 +46,102 @@ impl fmt::Display for ProgramReport {
         let exe = "the program";
 
         writeln!(f, "{exe} exited unsuccessfully")?;
-        write!(f, "{report}")
+        if !self.0.is::<Diagnostics>() {
+            write!(f, "{report}")?;
+        }
+
+        // Display the full diagnostics for any errors caused by diagnostics.
+        let errors = core::iter::successors(Some(self.0.as_ref()), |&error| error.source());
+        for error in errors.take(MAX_DEPTH) {
+            if let Some(diagnostics) = error.downcast_ref::<Diagnostics>() {
+                write!(f, "\n{diagnostics}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+    use core::{error::Error, fmt};
+
+    use crate::{
+        ProgramReport,
+        diagnostic::{Context, Diagnostic, Diagnostics, Span},
+    };
+
+    #[derive(Debug)]
+    struct InvalidConfig(Diagnostics);
+    impl fmt::Display for InvalidConfig {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "config file is invalid")
+        }
+    }
+    impl Error for InvalidConfig {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    fn diagnostics() -> Diagnostics {
+        let source = "{\n  \"port\": \"eighty\"\n}";
+
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        diagnostics.push(
+            Diagnostic::error("`/port` is not of type `integer`").context(
+                Context::new(source, Span::default().line(2).column(11).length(8))
+                    .label("expected an integer"),
+            ),
+        );
+        diagnostics
+    }
+
+    #[test]
+    fn displays_diagnostics_caused_error() {
+        let output = ProgramReport::from(InvalidConfig(diagnostics())).to_string();
+        assert!(output.contains("config file is invalid"));
+        assert_eq!(
+            1,
+            output.matches("`/port` is not of type `integer`").count()
+        );
+        assert_eq!(
+            1,
+            output.matches("validating JSON generated 1 errors").count()
+        );
+    }
+
+    #[test]
+    fn displays_diagnostics() {
+        let output = ProgramReport::from_diagnostics(diagnostics()).to_string();
+        assert!(output.contains("exited unsuccessfully"));
+        assert_eq!(
+            1,
+            output.matches("`/port` is not of type `integer`").count()
+        );
+        assert!(output.contains("\"port\": \"eighty\""));
+        assert!(output.contains("^^^^^^^^ expected an integer"));
+        assert!(output.contains("validating JSON generated 1 errors"));
+    }
+
+    #[test]
+    fn truncates_cyclic_sources() {
+        #[derive(Debug)]
+        struct Cyclic;
+        impl fmt::Display for Cyclic {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "some message")
+            }
+        }
+        impl Error for Cyclic {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(self)
+            }
+        }
+
+        let output = ProgramReport::from(Cyclic).to_string();
+        assert!(output.contains("exited unsuccessfully"));
+        assert!(output.ends_with(" ... (error chain truncated)\n"));
     }
 }
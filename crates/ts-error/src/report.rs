@@ -1,9 +1,18 @@
 //! Display an error stack by traversing their source.
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, format, string::ToString};
 use core::{error::Error, fmt};
 
-use ts_ansi::style::{BOLD, DEFAULT, RED, RESET};
+use ts_ansi::{
+    style::{BOLD, DEFAULT, RED, RESET},
+    wrap, write_styled,
+};
+
+use crate::diagnostic::Diagnostics;
+
+/// The maximum number of errors in a chain to display, guards against error sources that form a
+/// cycle.
+pub(crate) const MAX_DEPTH: usize = 64;
 
 /// Trait for converting something into an error report.
 pub trait IntoReport<T> {
@@ -17,17 +26,176 @@ impl<T, E: Error + 'static> IntoReport<T> for Result<T, E> {
     }
 }
 
+/// Trait for converting something that is not an error, but can be displayed, into an error
+/// report. Prefer [`IntoReport`] for errors, as this discards the error source.
+pub trait IntoReportDisplay<T> {
+    /// Convert self into an error report if self is an error.
+    fn into_report_display(self) -> Result<T, Report<'static>>;
+}
+
+impl<T, D: fmt::Display + 'static> IntoReportDisplay<T> for Result<T, D> {
+    fn into_report_display(self) -> Result<T, Report<'static>> {
+        self.map_err(|message| Report::from_display(message))
+    }
+}
+
+/// An error that only has a message.
+struct DisplayError<D>(D);
+impl<D: fmt::Display> fmt::Debug for DisplayError<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl<D: fmt::Display> fmt::Display for DisplayError<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl<D: fmt::Display> Error for DisplayError<D> {}
+
+/// The layout of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportStyle {
+    /// A numbered list of errors.
+    Numbered,
+    /// The error, followed by a `Caused by:` block of indexed sources.
+    CausedBy,
+}
+
 /// An error report, displays the error stack of some error.
 pub struct Report<'e> {
     /// The error for this report.
     pub source: Box<dyn Error + 'e>,
+    /// The layout of the report.
+    style: ReportStyle,
+    /// The width to wrap the report to.
+    max_width: Option<usize>,
+    /// If sources that are diagnostics should be skipped, as they are displayed separately.
+    skip_diagnostics: bool,
 }
 impl<'e> Report<'e> {
     /// Create a new error report.
     pub fn new<E: Error + 'e>(source: E) -> Self {
         Self {
             source: Box::new(source),
+            style: ReportStyle::Numbered,
+            max_width: None,
+            skip_diagnostics: false,
+        }
+    }
+
+    /// Create a new error report from something that is not an error, but can be displayed.
+    pub fn from_display<D: fmt::Display + 'e>(message: D) -> Self {
+        Self::new(DisplayError(message))
+    }
+
+    /// Display the report as the error, followed by a `Caused by:` block of indexed sources.
+    pub fn caused_by_style(mut self) -> Self {
+        self.style = ReportStyle::CausedBy;
+        self
+    }
+
+    /// Wrap the report so no line is wider than `width` visible columns, continuation lines are
+    /// aligned with the start of the message.
+    pub fn wrapped(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// Skip sources that are diagnostics, for when the diagnostics are displayed in full after the
+    /// report.
+    pub(crate) fn skip_diagnostics(mut self) -> Self {
+        self.skip_diagnostics = true;
+        self
+    }
+
+    /// Returns the next source of an error to display, skipping diagnostics if the report skips
+    /// them.
+    fn next_source<'s>(&self, error: &'s dyn Error) -> Option<&'s (dyn Error + 'static)> {
+        let mut source = error.source();
+        while self.skip_diagnostics
+            && let Some(inner) = source
+            && inner.is::<Diagnostics>()
+        {
+            source = inner.source();
+        }
+
+        source
+    }
+
+    /// Write the report with styling.
+    fn write_to(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        match self.style {
+            ReportStyle::Numbered => {
+                let mut current_error = Some(self.source.as_ref());
+                let mut count: usize = 1;
+
+                while let Some(error) = current_error {
+                    if count > MAX_DEPTH {
+                        writeln!(f, " ... (error chain truncated)")?;
+                        break;
+                    }
+
+                    let prefix = format!(" {BOLD}{RED}{count}{DEFAULT}.{RESET} ");
+                    let prefix_width = count.to_string().len() + 3;
+                    self.write_message(f, &prefix, prefix_width, error)?;
+
+                    count += 1;
+                    current_error = self.next_source(error);
+                }
+            }
+            ReportStyle::CausedBy => {
+                let prefix = format!("{BOLD}{RED}Error{DEFAULT}:{RESET} ");
+                self.write_message(f, &prefix, 7, &self.source)?;
+
+                let mut current_error = self.next_source(self.source.as_ref());
+                if current_error.is_some() {
+                    writeln!(f)?;
+                    writeln!(f, "{BOLD}Caused by{RESET}:")?;
+                }
+
+                let mut index: usize = 0;
+                while let Some(error) = current_error {
+                    if index + 1 >= MAX_DEPTH {
+                        writeln!(f, "    ... (error chain truncated)")?;
+                        break;
+                    }
+
+                    let prefix = format!("    {index}: ");
+                    self.write_message(f, &prefix, prefix.len(), error)?;
+
+                    index += 1;
+                    current_error = self.next_source(error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a message following a prefix, wrapping the message if the report is wrapped.
+    fn write_message(
+        &self,
+        f: &mut dyn fmt::Write,
+        prefix: &str,
+        prefix_width: usize,
+        message: &dyn fmt::Display,
+    ) -> fmt::Result {
+        let Some(max_width) = self.max_width else {
+            return writeln!(f, "{prefix}{message}");
+        };
+
+        let indent = " ".repeat(prefix_width);
+        let lines = wrap(&message.to_string(), max_width.saturating_sub(prefix_width));
+        for (index, line) in lines.iter().enumerate() {
+            if index == 0 {
+                writeln!(f, "{prefix}{line}")?;
+            } else {
+                writeln!(f, "{indent}{line}")?;
+            }
         }
+
+        Ok(())
     }
 }
 impl Error for Report<'static> {
@@ -42,16 +210,170 @@ impl fmt::Debug for Report<'_> {
 }
 impl fmt::Display for Report<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut current_error = Some(self.source.as_ref());
-        let mut count = 1;
+        write_styled(f, |f| self.write_to(f))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{
+        boxed::Box,
+        format,
+        string::{String, ToString},
+    };
+    use core::{error::Error, fmt};
 
-        while let Some(error) = current_error {
-            writeln!(f, " {BOLD}{RED}{count}{DEFAULT}.{RESET} {error}")?;
+    use ts_ansi::{
+        style::{BOLD, DEFAULT, RED, RESET},
+        visible_width,
+    };
 
-            count += 1;
-            current_error = error.source();
+    use crate::{IntoReportDisplay, Report, report::MAX_DEPTH};
+
+    #[derive(Debug)]
+    struct Inner;
+    impl fmt::Display for Inner {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "inner failure")
         }
+    }
+    impl Error for Inner {}
 
-        Ok(())
+    #[derive(Debug)]
+    struct Outer(Inner);
+    impl fmt::Display for Outer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "outer failure")
+        }
+    }
+    impl Error for Outer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    fn styled(report: &Report<'_>) -> String {
+        let mut output = String::new();
+        report
+            .write_to(&mut output)
+            .expect("writing to a string should succeed");
+        output
+    }
+
+    #[test]
+    fn displays_numbered() {
+        let report = Report::new(Outer(Inner));
+        assert_eq!(
+            format!(
+                " {BOLD}{RED}1{DEFAULT}.{RESET} outer failure\n {BOLD}{RED}2{DEFAULT}.{RESET} inner failure\n"
+            ),
+            styled(&report)
+        );
+    }
+
+    #[test]
+    fn displays_caused_by() {
+        let report = Report::new(Outer(Inner)).caused_by_style();
+        assert_eq!(
+            format!(
+                "{BOLD}{RED}Error{DEFAULT}:{RESET} outer failure\n\n{BOLD}Caused by{RESET}:\n    0: inner failure\n"
+            ),
+            styled(&report)
+        );
+
+        let report = Report::new(Inner).caused_by_style();
+        assert_eq!(
+            format!("{BOLD}{RED}Error{DEFAULT}:{RESET} inner failure\n"),
+            styled(&report)
+        );
+    }
+
+    #[test]
+    fn reports_displayable() {
+        let result: Result<(), String> = Err("some message".to_string());
+        let report = result
+            .into_report_display()
+            .expect_err("result should be an error");
+        assert_eq!(
+            format!(" {BOLD}{RED}1{DEFAULT}.{RESET} some message\n"),
+            styled(&report)
+        );
+        assert!(report.source.source().is_none());
+    }
+
+    #[test]
+    fn wraps_to_width() {
+        #[derive(Debug)]
+        struct Long(Option<Box<Self>>);
+        impl fmt::Display for Long {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "something went wrong while trying to do something that takes a long explanation"
+                )
+            }
+        }
+        impl Error for Long {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                let source: &Self = self.0.as_deref()?;
+                Some(source)
+            }
+        }
+
+        let long = Long(Some(Box::new(Long(Some(Box::new(Long(None)))))));
+        let output = Report::new(long).wrapped(40).to_string();
+
+        let visible = output
+            .replace(BOLD, "")
+            .replace(RED, "")
+            .replace(DEFAULT, "")
+            .replace(RESET, "");
+        assert!(visible.lines().count() > 3);
+        for line in visible.lines() {
+            assert!(visible_width(line) <= 40, "`{line}` is wider than 40");
+        }
+        assert!(visible.starts_with(" 1. something went wrong while trying to\n    do something"));
+
+        let long = Long(Some(Box::new(Long(None))));
+        let output = Report::new(long).caused_by_style().wrapped(40).to_string();
+        for line in output
+            .replace(BOLD, "")
+            .replace(RED, "")
+            .replace(DEFAULT, "")
+            .replace(RESET, "")
+            .lines()
+        {
+            assert!(visible_width(line) <= 40, "`{line}` is wider than 40");
+        }
+    }
+
+    #[test]
+    fn truncates_cyclic_sources() {
+        #[derive(Debug)]
+        struct Cyclic;
+        impl fmt::Display for Cyclic {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "some message")
+            }
+        }
+        impl Error for Cyclic {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(self)
+            }
+        }
+
+        let output = styled(&Report::new(Cyclic));
+        assert_eq!(MAX_DEPTH + 1, output.lines().count());
+        assert!(output.ends_with(" ... (error chain truncated)\n"));
+
+        let output = styled(&Report::new(Cyclic).caused_by_style());
+        assert!(output.ends_with("    ... (error chain truncated)\n"));
+        assert_eq!(
+            MAX_DEPTH - 1,
+            output
+                .lines()
+                .filter(|line| line.ends_with(": some message"))
+                .count()
+        );
     }
 }
@@ -16,6 +16,14 @@ impl<T: Copy + Default> core::fmt::Display for Cursor<'_, T> {
 }
 
 impl<'a, T: Copy + Default> Cursor<'a, T> {
+    /// Create a new cursor at the start of a collection.
+    pub fn new(collection: &'a [T]) -> Self {
+        Self {
+            index: 0,
+            collection,
+        }
+    }
+
     /// Pull some items from this source into the specified buffer, returning how many items were
     /// read.
     ///
@@ -60,6 +68,46 @@ impl<'a, T: Copy + Default> Cursor<'a, T> {
 
         Ok(data)
     }
+
+    /// Advance the cursor past the next `count` items without reading them. The cursor is not
+    /// moved if it would go out of bounds.
+    pub fn skip(&mut self, count: usize) -> Result<(), OutOfBounds> {
+        self.read_count(count).map(|_| ())
+    }
+
+    /// Advance the cursor while the next item matches a predicate, returning how many items were
+    /// skipped.
+    pub fn skip_while<F: FnMut(T) -> bool>(&mut self, mut predicate: F) -> usize {
+        let count = self
+            .remaining_slice()
+            .iter()
+            .take_while(|&&item| predicate(item))
+            .count();
+        self.index += count;
+
+        count
+    }
+
+    /// Returns the items that have not been read yet, without advancing the cursor.
+    pub fn remaining_slice(&self) -> &'a [T] {
+        self.collection.get(self.index..).unwrap_or_default()
+    }
+
+    /// Returns the items that have been read, without advancing the cursor.
+    pub fn consumed_slice(&self) -> &'a [T] {
+        self.collection.get(..self.index).unwrap_or_default()
+    }
+
+    /// Take the next `length` items as a cursor of their own, the source is advanced past them.
+    pub fn sub_cursor(&mut self, length: usize) -> Result<Self, OutOfBounds> {
+        let collection = self
+            .collection
+            .get(self.index..self.index + length)
+            .ok_or_else(|| OutOfBounds::new(length))?;
+        self.index += length;
+
+        Ok(Self::new(collection))
+    }
 }
 
 impl std::io::Read for Cursor<'_, u8> {
@@ -69,7 +117,7 @@ impl std::io::Read for Cursor<'_, u8> {
 }
 
 /// A read would take the cursor out of bounds.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct OutOfBounds {
     /// The number of items requested.
@@ -91,3 +139,58 @@ impl core::fmt::Display for OutOfBounds {
     }
 }
 impl core::error::Error for OutOfBounds {}
+
+#[cfg(test)]
+mod test {
+    use crate::Cursor;
+
+    #[test]
+    fn handles_sub_cursor() {
+        let data = [1u8, 2, 3, 4, 5];
+        let mut cursor = Cursor::new(&data);
+
+        let mut sub_cursor = cursor
+            .sub_cursor(3)
+            .expect("sub cursor should be in bounds");
+        assert_eq!(Ok([1, 2, 3]), sub_cursor.read_array::<3>());
+        assert!(sub_cursor.read_count(1usize).is_err());
+
+        assert_eq!(Ok([4, 5]), cursor.read_array::<2>());
+        assert!(cursor.sub_cursor(1).is_err());
+    }
+
+    #[test]
+    fn borrows_slices() {
+        let data = [1u8, 2, 3, 4, 5];
+        let mut cursor = Cursor::new(&data);
+        assert!(cursor.consumed_slice().is_empty());
+        assert_eq!(&data, cursor.remaining_slice());
+
+        assert_eq!(Ok([1, 2]), cursor.read_array::<2>());
+        assert_eq!(&[1, 2], cursor.consumed_slice());
+        assert_eq!(&[3, 4, 5], cursor.remaining_slice());
+        assert_eq!(&[3, 4, 5], cursor.remaining_slice());
+        assert_eq!(Ok([3, 4, 5]), cursor.read_array::<3>());
+
+        assert_eq!(&data, cursor.consumed_slice());
+        assert!(cursor.remaining_slice().is_empty());
+    }
+
+    #[test]
+    fn skips_items() {
+        let data = [0u8, 0, 0, 4, 5];
+        let mut cursor = Cursor::new(&data);
+
+        assert_eq!(3, cursor.skip_while(|item| item == 0));
+        assert_eq!(0, cursor.skip_while(|item| item == 0));
+        assert_eq!(3, cursor.index);
+
+        assert!(cursor.skip(3).is_err());
+        assert_eq!(3, cursor.index);
+
+        assert_eq!(Ok(()), cursor.skip(1));
+        assert_eq!(Ok([5]), cursor.read_array::<1>());
+        assert_eq!(Ok(()), cursor.skip(0));
+        assert_eq!(0, cursor.skip_while(|_| true));
+    }
+}
@@ -5,7 +5,11 @@
 extern crate alloc;
 
 mod cursor;
+mod read_exact;
 mod read_file;
+mod vec_writer;
 
 pub use cursor::{Cursor, OutOfBounds};
-pub use read_file::{ReadFileError, read_file, read_file_to_string};
+pub use read_exact::read_exact_or_eof;
+pub use read_file::{ReadFileError, read_file, read_file_tagged, read_file_to_string};
+pub use vec_writer::VecWriter;
@@ -0,0 +1,68 @@
+//! Read an exact number of bytes from a reader.
+
+use alloc::{format, vec::Vec};
+use std::io::{self, Read};
+
+/// Read exactly `n` bytes from a reader, returning an [`io::ErrorKind::UnexpectedEof`] error if
+/// the reader ends before `n` bytes are read.
+pub fn read_exact_or_eof(r: &mut impl Read, n: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let limit = u64::try_from(n).unwrap_or(u64::MAX);
+    r.take(limit).read_to_end(&mut bytes)?;
+
+    if bytes.len() < n {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("expected {n} bytes but only {} were read", bytes.len()),
+        ));
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Read};
+
+    use crate::read_exact_or_eof;
+
+    /// A reader that returns at most one byte per read.
+    struct Trickle<'a>(&'a [u8]);
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let (Some(byte), Some(target)) = (self.0.first(), buf.first_mut()) else {
+                return Ok(0);
+            };
+            *target = *byte;
+            self.0 = self.0.get(1..).unwrap_or_default();
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn reads_exact_bytes() {
+        let mut reader = Trickle(&[1, 2, 3, 4, 5]);
+        assert_eq!(
+            vec![1, 2, 3],
+            read_exact_or_eof(&mut reader, 3).expect("reading should succeed")
+        );
+        assert_eq!(
+            vec![4, 5],
+            read_exact_or_eof(&mut reader, 2).expect("reading should succeed")
+        );
+        assert!(
+            read_exact_or_eof(&mut reader, 0)
+                .expect("reading should succeed")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn errors_on_short_read() {
+        let mut reader = Trickle(&[1, 2, 3]);
+        let error = read_exact_or_eof(&mut reader, 5).expect_err("reading should fail");
+
+        assert_eq!(io::ErrorKind::UnexpectedEof, error.kind());
+        assert_eq!("expected 5 bytes but only 3 were read", error.to_string());
+    }
+}
@@ -5,7 +5,8 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use ts_path::DisplayPath;
+use ts_error::diagnostic::Diagnostic;
+use ts_path::{DisplayPath, NormalizePath};
 
 /// Error variants for reading a file.
 #[derive(Debug)]
@@ -15,6 +16,9 @@ pub enum ReadFileError {
     #[non_exhaustive]
     DoesNotExist { path: PathBuf },
 
+    #[non_exhaustive]
+    BrokenSymlink { path: PathBuf, target: PathBuf },
+
     #[non_exhaustive]
     NotAFile { path: PathBuf },
 
@@ -27,6 +31,14 @@ impl core::fmt::Display for ReadFileError {
             Self::DoesNotExist { path, .. } => {
                 write!(f, "`{}` does not exist", path.opinionated_display())
             }
+            Self::BrokenSymlink { path, target } => {
+                write!(
+                    f,
+                    "`{}` is a symbolic link to `{}`, which does not exist",
+                    path.opinionated_display(),
+                    target.opinionated_display()
+                )
+            }
             Self::NotAFile { path, .. } => {
                 write!(f, "`{}` is not a file", path.opinionated_display())
             }
@@ -45,6 +57,27 @@ impl core::error::Error for ReadFileError {
     }
 }
 impl ReadFileError {
+    /// Returns the path of the file that could not be read.
+    pub fn path(&self) -> &Path {
+        match &self {
+            Self::DoesNotExist { path, .. }
+            | Self::BrokenSymlink { path, .. }
+            | Self::NotAFile { path, .. }
+            | Self::ReadError { path, .. } => path,
+        }
+    }
+
+    /// Returns the error as an error diagnostic, so it can be reported alongside other
+    /// diagnostics. The diagnostic has no context, only the headline and the path of the file.
+    pub fn as_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self).file_path(self.path().opinionated_display());
+
+        match &self {
+            Self::ReadError { source, .. } => diagnostic.add_note(source),
+            _ => diagnostic,
+        }
+    }
+
     #[allow(clippy::missing_docs_in_private_items)]
     pub(crate) fn read_error(source: io::Error, path: &Path) -> Self {
         Self::ReadError {
@@ -52,14 +85,29 @@ impl ReadFileError {
             source,
         }
     }
+
+    /// Create the error for a path that does not exist, distinguishing symbolic links whose
+    /// target does not exist.
+    pub(crate) fn does_not_exist(path: &Path) -> Self {
+        let is_symlink = fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink());
+
+        if is_symlink && let Ok(target) = fs::read_link(path) {
+            Self::BrokenSymlink {
+                path: path.to_path_buf(),
+                target,
+            }
+        } else {
+            Self::DoesNotExist {
+                path: path.to_path_buf(),
+            }
+        }
+    }
 }
 
 /// Read a file, returning presentable error variants.
 pub fn read_file(path: &Path) -> Result<Vec<u8>, ReadFileError> {
     if !fs::exists(path).map_err(|source| ReadFileError::read_error(source, path))? {
-        return Err(ReadFileError::DoesNotExist {
-            path: path.to_path_buf(),
-        });
+        return Err(ReadFileError::does_not_exist(path));
     }
 
     let metadata = path
@@ -75,12 +123,16 @@ pub fn read_file(path: &Path) -> Result<Vec<u8>, ReadFileError> {
     fs::read(path).map_err(|source| ReadFileError::read_error(source, path))
 }
 
+/// Read a file, returning the normalized path of the file alongside its contents, so the contents
+/// stay tagged with where they came from. Returns presentable error variants.
+pub fn read_file_tagged(path: &Path) -> Result<(PathBuf, Vec<u8>), ReadFileError> {
+    read_file(path).map(|contents| (path.normalized(), contents))
+}
+
 /// Read a file to a string, returning presentable error variants.
 pub fn read_file_to_string(path: &Path) -> Result<String, ReadFileError> {
     if !fs::exists(path).map_err(|source| ReadFileError::read_error(source, path))? {
-        return Err(ReadFileError::DoesNotExist {
-            path: path.to_path_buf(),
-        });
+        return Err(ReadFileError::does_not_exist(path));
     }
 
     let metadata = path
@@ -95,3 +147,94 @@ pub fn read_file_to_string(path: &Path) -> Result<String, ReadFileError> {
 
     fs::read_to_string(path).map_err(|source| ReadFileError::read_error(source, path))
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn tags_contents_with_path() {
+        use std::{env, fs};
+
+        use ts_path::NormalizePath;
+
+        use crate::read_file_tagged;
+
+        let directory = env::temp_dir().join("ts-io-read-file-tagged");
+        fs::create_dir_all(directory.join("nested")).expect("creating directory should succeed");
+        let path = directory.join("file.txt");
+        fs::write(&path, "contents").expect("writing file should succeed");
+
+        let result = read_file_tagged(
+            &directory
+                .join(".")
+                .join("nested")
+                .join("..")
+                .join("file.txt"),
+        );
+
+        fs::remove_dir_all(&directory).expect("removing directory should succeed");
+
+        let (tagged_path, contents) = result.expect("reading file should succeed");
+        assert_eq!(path.normalized(), tagged_path);
+        assert_eq!(b"contents".as_slice(), contents);
+    }
+
+    #[test]
+    fn converts_to_diagnostic() {
+        use std::env;
+
+        use ts_error::diagnostic::Severity;
+        use ts_path::DisplayPath;
+
+        use crate::read_file;
+
+        let path = env::temp_dir().join("ts-io-missing-file.json");
+        let error = read_file(&path).expect_err("reading a missing file should fail");
+
+        let diagnostic = error.as_diagnostic();
+        let display_path = path.opinionated_display();
+        assert_eq!(Severity::Error, diagnostic.severity);
+        assert_eq!(
+            format!("`{display_path}` does not exist"),
+            diagnostic.headline
+        );
+        assert_eq!(Some(display_path), diagnostic.file_path);
+        assert!(diagnostic.context.is_none());
+        assert_eq!(path.as_path(), error.path());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_broken_symlink() {
+        use std::{env, fs, os::unix::fs::symlink};
+
+        use crate::{ReadFileError, read_file, read_file_to_string};
+
+        let directory = env::temp_dir().join("ts-io-broken-symlink");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).expect("creating directory should succeed");
+
+        let link = directory.join("link");
+        let target = directory.join("missing");
+        symlink(&target, &link).expect("creating symlink should succeed");
+
+        let read_result = read_file(&link);
+        let read_to_string_result = read_file_to_string(&link);
+        let missing_result = read_file(&target);
+
+        fs::remove_dir_all(&directory).expect("removing directory should succeed");
+
+        assert!(matches!(
+            read_result,
+            Err(ReadFileError::BrokenSymlink { path, target: link_target })
+                if path == link && link_target == target
+        ));
+        assert!(matches!(
+            read_to_string_result,
+            Err(ReadFileError::BrokenSymlink { .. })
+        ));
+        assert!(matches!(
+            missing_result,
+            Err(ReadFileError::DoesNotExist { .. })
+        ));
+    }
+}
@@ -0,0 +1,63 @@
+//! A simple writer to build a buffer.
+
+use alloc::vec::Vec;
+
+/// A writer that appends to a growable buffer.
+#[derive(Clone, Debug, Default)]
+pub struct VecWriter {
+    /// The buffer.
+    buffer: Vec<u8>,
+}
+
+impl VecWriter {
+    /// Create a new, empty, writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new, empty, writer with at least the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the bytes written so far.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Consume the writer, returning the written bytes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+impl std::io::Write for VecWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use crate::VecWriter;
+
+    #[test]
+    fn builds_buffer() {
+        let mut writer = VecWriter::new();
+        let name = "payload";
+        write!(writer, "{name}-{}", 42).expect("writing should succeed");
+        writer.write_all(b"\x00").expect("writing should succeed");
+
+        assert_eq!(b"payload-42\x00", writer.as_slice());
+        assert_eq!(b"payload-42\x00".to_vec(), writer.into_inner());
+    }
+}
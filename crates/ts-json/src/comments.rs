@@ -0,0 +1,244 @@
+//! Linting the comments of JSON with comments.
+
+use core::ops::Range;
+use std::path::Path;
+
+use serde_json::Value;
+use ts_error::diagnostic::{Context, Diagnostic, Diagnostics, SourceIndex, Span};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A comment in a source.
+struct Comment<'a> {
+    /// The span of the comment, including its markers.
+    span: Span,
+    /// The text of the comment, without its markers.
+    text: &'a str,
+    /// The byte range of the comment in the source, including its markers.
+    range: Range<usize>,
+}
+
+/// Lint the comments of some JSON with comments, returning an info diagnostic for each comment that
+/// contains a `"key": value` pair, as the comment may be a setting that was disabled and forgotten.
+///
+/// Both `//` and `/* */` comments are linted. Comments of ordinary prose are not reported.
+pub fn lint_commented_settings(source: &str, source_path: Option<&Path>) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new("linting JSON comments");
+    let index = SourceIndex::new(source);
+
+    for comment in comments(source) {
+        let Some(key) = commented_setting(comment.text) else {
+            continue;
+        };
+
+        let mut diagnostic = Diagnostic::info(format!("`{key}` is commented out"))
+            .context(
+                Context::from_index(&index, comment.span)
+                    .label("this comment looks like a setting"),
+            )
+            .add_note("remove the comment markers if the setting should be enabled");
+        diagnostic.file_path = source_path.map(|path| path.display().to_string());
+        diagnostics.push(diagnostic);
+    }
+
+    diagnostics
+}
+
+/// Returns some JSON with comments with each of its comments replaced by spaces, so the source can
+/// be parsed as JSON.
+///
+/// Line breaks inside comments are kept, so the lines and columns of the rest of the source are
+/// unchanged, and spans into the stripped source also locate the original source.
+pub fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut copied = 0;
+
+    for comment in comments(source) {
+        stripped.push_str(source.get(copied..comment.range.start).unwrap_or_default());
+        for grapheme in source
+            .get(comment.range.clone())
+            .unwrap_or_default()
+            .graphemes(true)
+        {
+            if grapheme.contains('\n') {
+                stripped.push_str(grapheme);
+            } else {
+                stripped.push(' ');
+            }
+        }
+        copied = comment.range.end;
+    }
+    stripped.push_str(source.get(copied..).unwrap_or_default());
+
+    stripped
+}
+
+/// Returns the comments in a source, comment markers inside strings are ignored.
+fn comments(source: &str) -> Vec<Comment<'_>> {
+    let mut comments = Vec::new();
+    let mut span = Span::default();
+    let mut graphemes = source.grapheme_indices(true).peekable();
+    let mut in_string = false;
+    let mut is_escaped = false;
+
+    while let Some((offset, grapheme)) = graphemes.next() {
+        let start = span;
+        advance(&mut span, grapheme);
+
+        if in_string {
+            if is_escaped {
+                is_escaped = false;
+            } else if grapheme == "\\" {
+                is_escaped = true;
+            } else if grapheme == "\"" {
+                in_string = false;
+            }
+            continue;
+        }
+
+        let is_block = match (grapheme, graphemes.peek()) {
+            ("\"", _) => {
+                in_string = true;
+                continue;
+            }
+            ("/", Some((_, "/"))) => false,
+            ("/", Some((_, "*"))) => true,
+            _ => continue,
+        };
+
+        graphemes.next();
+        advance(&mut span, "/");
+        let text_start = offset + 2;
+        let mut text_end = source.len();
+        let mut end = source.len();
+        let mut previous = "";
+
+        while let Some(&(next_offset, next)) = graphemes.peek() {
+            if !is_block && next.contains('\n') {
+                text_end = next_offset;
+                end = next_offset;
+                break;
+            }
+
+            graphemes.next();
+            advance(&mut span, next);
+
+            if is_block && previous == "*" && next == "/" {
+                text_end = next_offset - 1;
+                end = next_offset + 1;
+                break;
+            }
+            previous = next;
+        }
+
+        let text = source.get(text_start..text_end).unwrap_or_default();
+        let first_line = source
+            .get(offset..text_end)
+            .and_then(|comment| comment.lines().next())
+            .unwrap_or_default();
+        let length = if is_block && !text.contains('\n') {
+            first_line.graphemes(true).count() + 2
+        } else {
+            first_line.graphemes(true).count()
+        };
+
+        comments.push(Comment {
+            span: start.length(length.max(1)),
+            text,
+            range: offset..end,
+        });
+    }
+
+    comments
+}
+
+/// Returns the first key of the `"key": value` pairs in the text of a comment, if the text is only
+/// `"key": value` pairs.
+fn commented_setting(text: &str) -> Option<String> {
+    let text = text.trim().trim_end_matches(',');
+    if !text.starts_with('"') {
+        return None;
+    }
+
+    let Ok(Value::Object(object)) = serde_json::from_str(&format!("{{{text}}}")) else {
+        return None;
+    };
+
+    object.keys().next().cloned()
+}
+
+/// Advance a span past a grapheme.
+fn advance(span: &mut Span, grapheme: &str) {
+    if grapheme.contains('\n') {
+        span.advance('\n');
+    } else if grapheme != "\r" {
+        span.advance_columns(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ts_error::diagnostic::{Severity, Span};
+
+    use crate::{lint_commented_settings, strip_comments};
+
+    #[test]
+    fn flags_commented_settings() {
+        let source = r#"{
+  // "timeout": 30,
+  "retries": 3, /* "verbose": true */
+  // the number of times to retry: "retries" must be positive
+  "url": "https://example.com" // a URL
+}"#;
+
+        let diagnostics = lint_commented_settings(source, None);
+        assert_eq!(2, diagnostics.problems.len());
+        assert!(
+            diagnostics
+                .problems
+                .iter()
+                .all(|diagnostic| diagnostic.severity == Severity::Info)
+        );
+
+        let spans: Vec<_> = diagnostics
+            .problems
+            .iter()
+            .filter_map(|diagnostic| diagnostic.context.as_ref())
+            .map(|context| context.span)
+            .collect();
+        assert_eq!(
+            vec![Span::at(2, 3).length(17), Span::at(3, 17).length(21)],
+            spans
+        );
+        assert_eq!(
+            Some("`timeout` is commented out"),
+            diagnostics
+                .problems
+                .first()
+                .map(|diagnostic| diagnostic.headline.as_str())
+        );
+    }
+
+    #[test]
+    fn ignores_prose_comments() {
+        let source = "{\n  // retry up to three times: more is slow\n  \"retries\": 3\n}";
+        assert!(lint_commented_settings(source, None).is_empty());
+    }
+
+    #[test]
+    fn strips_comments() {
+        let source =
+            "{\n  // \"timeout\": 30,\n  \"url\": \"a // b\", /* 🦀\n */ \"retries\": 3\n}";
+        let stripped = strip_comments(source);
+        assert_eq!(
+            "{\n                   \n  \"url\": \"a // b\",     \n    \"retries\": 3\n}",
+            stripped
+        );
+
+        let value: serde_json::Value =
+            serde_json::from_str(&stripped).expect("stripped source should be valid JSON");
+        assert_eq!(
+            Some(3),
+            value.get("retries").and_then(serde_json::Value::as_u64)
+        );
+    }
+}
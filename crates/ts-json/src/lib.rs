@@ -2,25 +2,33 @@
 //!
 //! JSON schema validation and reporting
 
+mod comments;
 mod location;
-mod parser;
+pub mod parser;
 mod problem_message;
+mod snippet;
+mod validator;
+#[cfg(feature = "yaml")]
+mod yaml;
 
+use core::time::Duration;
 use std::path::Path;
 
 use jsonschema::ValidationOptions;
 use serde_json::Value;
-use ts_error::{
-    diagnostic::{Context, Diagnostic, Diagnostics},
-    normalize_message,
-};
+use ts_error::diagnostic::{Context, Diagnostic, Diagnostics, SourceIndex};
 
 use crate::{
-    location::LocationExtensions,
-    parser::{Node, Value as SpannedValue},
-    problem_message::ProblemMessage,
+    parser::Node,
+    validator::{locate_problem, problem_diagnostic, validate_source},
 };
 
+pub use comments::{lint_commented_settings, strip_comments};
+pub use snippet::span_text;
+pub use validator::{ObjectReport, ValidateOptions, Validator, syntax_diagnostic};
+#[cfg(feature = "yaml")]
+pub use yaml::validate_yaml;
+
 /// Error variants for validating JSON.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -32,6 +40,10 @@ pub enum ValidationError {
     #[non_exhaustive]
     ParseSchema { source: serde_json::Error },
 
+    #[cfg(feature = "yaml")]
+    #[non_exhaustive]
+    ParseYaml { source: yaml_rust2::ScanError },
+
     #[non_exhaustive]
     CreateValidator {
         source: Box<jsonschema::ValidationError<'static>>,
@@ -42,6 +54,8 @@ impl core::fmt::Display for ValidationError {
         match &self {
             Self::ParseSource { .. } => write!(f, "source file is not valid JSON"),
             Self::ParseSchema { .. } => write!(f, "schema is not valid JSON"),
+            #[cfg(feature = "yaml")]
+            Self::ParseYaml { .. } => write!(f, "source file is not valid YAML"),
             Self::CreateValidator { .. } => write!(f, "could not create validator from schema"),
         }
     }
@@ -51,82 +65,207 @@ impl core::error::Error for ValidationError {
         match &self {
             Self::ParseSource { source, .. } | Self::ParseSchema { source, .. } => Some(source),
             Self::CreateValidator { source, .. } => Some(source),
+            #[cfg(feature = "yaml")]
+            Self::ParseYaml { source, .. } => Some(source),
         }
     }
 }
 
+/// How long each step of validating some JSON took, see [`validate_timed`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidateTimings {
+    /// How long parsing the source took.
+    pub parse: Duration,
+    /// How long compiling the schema took, this is saved by reusing a [`Validator`].
+    pub compile: Duration,
+    /// How long validating the source against the compiled schema took.
+    pub validate: Duration,
+}
+
 /// Validate some JSON against a JSON schema, returning all problems.
+///
+/// A trailing comma in the JSON is reported as an error diagnostic, rather than failing.
 pub fn validate(
     source: &str,
     schema: &str,
     source_path: Option<&Path>,
 ) -> Result<Diagnostics, ValidationError> {
-    let source_node: Value =
-        serde_json::from_str(source).map_err(|source| ValidationError::ParseSource { source })?;
-    let schema_node: Value =
-        serde_json::from_str(schema).map_err(|source| ValidationError::ParseSchema { source })?;
+    let mut diagnostics = Diagnostics::new("validating JSON");
+    validate_into(&mut diagnostics, source, schema, source_path)?;
+
+    Ok(diagnostics)
+}
+
+/// Validate some JSON against a JSON schema using some validation options, returning all problems.
+pub fn validate_with(
+    source: &str,
+    schema: &str,
+    source_path: Option<&Path>,
+    options: ValidateOptions,
+) -> Result<Diagnostics, ValidationError> {
+    Validator::new(schema)?
+        .options(options)
+        .validate(source, source_path)
+}
 
-    let validator = ValidationOptions::default()
-        .build(&schema_node)
-        .map_err(|source| ValidationError::CreateValidator {
-            source: Box::new(source),
-        })?;
+/// Validate some JSON against a JSON schema, pushing all problems into an existing collection of
+/// diagnostics.
+pub fn validate_into(
+    diagnostics: &mut Diagnostics,
+    source: &str,
+    schema: &str,
+    source_path: Option<&Path>,
+) -> Result<(), ValidationError> {
+    validate_source(
+        diagnostics,
+        source,
+        source_path,
+        || Validator::new(schema),
+        false,
+    )?;
 
+    Ok(())
+}
+
+/// Validate some JSON against a JSON schema, returning all problems alongside how long parsing the
+/// source, compiling the schema, and validating took.
+///
+/// If the source has a trailing comma, the schema is not compiled, so only the parse timing is
+/// set.
+pub fn validate_timed(
+    source: &str,
+    schema: &str,
+    source_path: Option<&Path>,
+) -> Result<(Diagnostics, ValidateTimings), ValidationError> {
     let mut diagnostics = Diagnostics::new("validating JSON");
+    let timings = validate_source(
+        &mut diagnostics,
+        source,
+        source_path,
+        || Validator::new(schema),
+        false,
+    )?;
 
-    if !validator.is_valid(&source_node) {
-        let document = Node::parse_document(source);
-        for error in validator.iter_errors(&source_node) {
-            let context = document.as_ref().and_then(|document| {
-                let span = document
-                    .evaluate(&error.instance_path)
-                    .map(|node| match node.value {
-                        SpannedValue::Array(_) | SpannedValue::Object(_) => {
-                            if let Some(tag) = &node.tag {
-                                tag.span
-                            } else {
-                                node.value.span()
-                            }
-                        }
-                        _ => node.value.span(),
-                    });
-
-                span.map(|span| {
-                    let mut context = Context::new(source, span);
-                    context.label = error.kind.message();
-                    context
-                })
-            });
-
-            let mut diagnostic = Diagnostic::error(format!(
-                "`{}` {}",
-                error.instance_path,
-                error.kind.headline()
-            ));
+    Ok((diagnostics, timings))
+}
+
+/// Validate some JSON against a JSON schema, returning only the first problem, if there is one.
+pub fn validate_first(
+    source: &str,
+    schema: &str,
+    source_path: Option<&Path>,
+) -> Result<Option<Diagnostic>, ValidationError> {
+    Validator::new(schema)?.validate_first(source, source_path)
+}
+
+/// Analyze the object at a JSON pointer in some JSON against a JSON schema, returning the required
+/// properties it is missing and the properties it has that are not allowed.
+pub fn analyze_object(
+    source: &str,
+    schema: &str,
+    pointer: &str,
+) -> Result<ObjectReport, ValidationError> {
+    Validator::new(schema)?.analyze_object(source, pointer)
+}
+
+/// Returns the JSON pointers of the optional properties defined by a JSON schema that are not set
+/// in some JSON.
+pub fn unset_optional_properties(
+    source: &str,
+    schema: &str,
+) -> Result<Vec<String>, ValidationError> {
+    Validator::new(schema)?.unset_optional_properties(source)
+}
 
-            diagnostic.context = context;
-            diagnostic.file_path = source_path.map(|path| path.display().to_string());
+/// Validate newline-delimited JSON against a JSON schema, where each line is validated as its own
+/// document, returning all problems located in the whole source.
+///
+/// * Blank lines are skipped.
+/// * Lines that are not valid JSON are reported as an error diagnostic.
+pub fn validate_ndjson(
+    source: &str,
+    schema: &str,
+    source_path: Option<&Path>,
+) -> Result<Diagnostics, ValidationError> {
+    let validator = Validator::new(schema)?;
+    let source_index = SourceIndex::new(source);
+    let mut diagnostics = Diagnostics::new("validating JSON");
 
-            if let Some(parent) = error.schema_path.parent()
-                && let Some(node) = schema_node.pointer(parent.join("description").as_str())
-                && let Some(contents) = node.as_str()
-            {
-                for line in contents.lines() {
-                    diagnostic.notes.push(normalize_message(line));
-                }
+    for (index, line) in source.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut record_diagnostics = Diagnostics::new("validating JSON");
+        match serde_json::from_str::<Value>(line) {
+            Ok(line_node) => {
+                validator.push_problems(&mut record_diagnostics, line, &line_node, source_path);
+            }
+            Err(error) => {
+                let mut diagnostic = syntax_diagnostic(&error, line, "record is not valid JSON");
+                diagnostic.file_path = source_path.map(|path| path.display().to_string());
+                record_diagnostics.push(diagnostic);
             }
+        }
 
-            diagnostics.push(diagnostic);
+        for diagnostic in record_diagnostics.problems {
+            diagnostics.push(relocate(diagnostic, &source_index, index));
         }
     }
 
     Ok(diagnostics)
 }
 
+/// Move the context, and related contexts, of a diagnostic over a single line to the line at
+/// `line_index` of the source.
+fn relocate(mut diagnostic: Diagnostic, index: &SourceIndex<'_>, line_index: usize) -> Diagnostic {
+    let line_delta = isize::try_from(line_index).unwrap_or(isize::MAX);
+
+    for context in diagnostic.context.iter_mut().chain(&mut diagnostic.related) {
+        let mut relocated = Context::from_index(index, context.span.shift(line_delta, 0));
+        relocated.label = context.label.take();
+        *context = relocated;
+    }
+
+    diagnostic
+}
+
+/// Validate a JSON schema, returning the problems that prevent it from compiling, located in the
+/// schema source.
+pub fn validate_schema(schema: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new("validating JSON schema");
+
+    let schema_node: Value = match serde_json::from_str(schema) {
+        Ok(schema_node) => schema_node,
+        Err(error) => {
+            diagnostics.push(syntax_diagnostic(
+                &error,
+                schema,
+                "schema is not valid JSON",
+            ));
+            return diagnostics;
+        }
+    };
+
+    if let Err(error) = ValidationOptions::default().build(&schema_node) {
+        let document = Node::parse_document(schema).ok();
+        diagnostics.push(problem_diagnostic(
+            &error,
+            &SourceIndex::new(schema),
+            locate_problem(&error, document.as_ref()),
+        ));
+    }
+
+    diagnostics
+}
+
 #[cfg(test)]
 mod test {
     use std::path::Path;
 
+    use ts_error::diagnostic::{Context, Diagnostic, Diagnostics, SourceIndex, Span};
+
     const SOURCE: &str = include_str!("../tests/sample.json");
     const SCHEMA: &str = include_str!("../tests/sample.schema.json");
 
@@ -142,4 +281,159 @@ mod test {
         assert_eq!(4, diagnostics.errors().count());
         eprintln!("{diagnostics}");
     }
+
+    #[test]
+    fn validates_into_existing_diagnostics() {
+        let overlay = r#"{ "type": "object", "required": ["overlay"] }"#;
+        let source_path = Some(Path::new("crates/ts-json/tests/sample.json"));
+
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        crate::validate_into(&mut diagnostics, SOURCE, SCHEMA, source_path)
+            .expect("validation to succeed");
+        crate::validate_into(&mut diagnostics, SOURCE, overlay, source_path)
+            .expect("validation to succeed");
+
+        assert_eq!(5, diagnostics.errors().count());
+    }
+
+    #[test]
+    fn reports_trailing_commas() {
+        let schema = r#"{ "type": "object" }"#;
+
+        let diagnostics =
+            crate::validate(r#"{"a":1,}"#, schema, None).expect("validation to succeed");
+        let diagnostic = diagnostics
+            .problems
+            .first()
+            .expect("there should be a problem");
+        assert_eq!("trailing comma is not allowed in JSON", diagnostic.headline);
+        assert_eq!(
+            Some(Span::at(1, 7)),
+            diagnostic.context.as_ref().map(|context| context.span)
+        );
+
+        let source = "{\n  \"list\": [\"a,\", \"b\" ,\n  ]\n}";
+        let diagnostic = crate::validate_first(source, schema, None)
+            .expect("validation to succeed")
+            .expect("there should be a problem");
+        assert_eq!(
+            Some(Span::at(2, 22)),
+            diagnostic.context.as_ref().map(|context| context.span)
+        );
+
+        assert!(crate::validate(r#"{"a":1"#, schema, None).is_err());
+    }
+
+    #[test]
+    fn times_validation() {
+        let source_path = Some(Path::new("crates/ts-json/tests/sample.json"));
+
+        let (diagnostics, timings) =
+            crate::validate_timed(SOURCE, SCHEMA, source_path).expect("validation to succeed");
+        assert_eq!(4, diagnostics.errors().count());
+        assert!(!timings.parse.is_zero());
+        assert!(!timings.compile.is_zero());
+        assert!(!timings.validate.is_zero());
+
+        let (diagnostics, timings) =
+            crate::validate_timed(r#"{"a":1,}"#, SCHEMA, None).expect("validation to succeed");
+        assert_eq!(1, diagnostics.errors().count());
+        assert!(timings.compile.is_zero());
+    }
+
+    #[test]
+    fn validates_first_problem() {
+        let source_path = Some(Path::new("crates/ts-json/tests/sample.json"));
+
+        let diagnostic = crate::validate_first(SOURCE, SCHEMA, source_path)
+            .expect("validation to succeed")
+            .expect("there should be a problem");
+        assert!(diagnostic.context.is_some());
+        assert_eq!(
+            Some("crates/ts-json/tests/sample.json"),
+            diagnostic.file_path.as_deref()
+        );
+
+        let diagnostics =
+            crate::validate(SOURCE, SCHEMA, source_path).expect("validation to succeed");
+        assert_eq!(
+            diagnostics
+                .problems
+                .first()
+                .map(|problem| problem.headline.as_str()),
+            Some(diagnostic.headline.as_str())
+        );
+
+        let schema = r#"{ "type": "object" }"#;
+        assert!(
+            crate::validate_first(SOURCE, schema, source_path)
+                .expect("validation to succeed")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn validates_ndjson_records() {
+        let schema = r#"{ "type": "object", "properties": { "id": { "type": "integer" } } }"#;
+        let source = "{ \"id\": 1 }\n\n{ \"id\": \"two\" }\n{ \"id\": \n{ \"id\": 4 }\n";
+
+        let diagnostics = crate::validate_ndjson(source, schema, Some(Path::new("records.ndjson")))
+            .expect("validation to succeed");
+        assert_eq!(2, diagnostics.errors().count());
+
+        let spans: Vec<_> = diagnostics
+            .problems
+            .iter()
+            .map(|problem| problem.context.as_ref().map(|context| context.span.line))
+            .collect();
+        assert_eq!(vec![Some(3), Some(4)], spans);
+
+        let output = diagnostics.to_string();
+        assert!(output.contains("records.ndjson:3:9"), "{output}");
+        assert!(output.contains("record is not valid JSON"));
+    }
+
+    #[test]
+    fn relocates_related_contexts() {
+        let source = "{ \"a\": 1 }\n{ \"a\": 1, \"a\": 2 }\n";
+        let record = "{ \"a\": 1, \"a\": 2 }";
+        let diagnostic = Diagnostic::error("duplicate key")
+            .context(Context::new(record, Span::at(1, 11).length(3)).label("duplicate"))
+            .related(Context::new(record, Span::at(1, 3).length(3)).label("first defined here"));
+
+        let relocated = crate::relocate(diagnostic, &SourceIndex::new(source), 1);
+
+        let context = relocated.context.expect("context should be kept");
+        assert_eq!(Span::at(2, 11).length(3), context.span);
+        assert_eq!(Some("duplicate"), context.label.as_deref());
+        assert_eq!(Some(&record.to_string()), context.context.last());
+
+        let related = relocated
+            .related
+            .first()
+            .expect("related context should be kept");
+        assert_eq!(Span::at(2, 3).length(3), related.span);
+        assert_eq!(Some("first defined here"), related.label.as_deref());
+    }
+
+    #[test]
+    fn locates_schema_problems() {
+        let schema = r#"{
+  "type": "object",
+  "properties": {
+    "a": { "type": "strnig" }
+  }
+}"#;
+        let diagnostics = crate::validate_schema(schema);
+        assert_eq!(1, diagnostics.errors().count());
+
+        let context = diagnostics
+            .problems
+            .first()
+            .and_then(|problem| problem.context.as_ref())
+            .expect("problem should have context");
+        assert_eq!(Span::default().line(4).column(20).length(8), context.span);
+
+        assert!(crate::validate_schema(SCHEMA).is_empty());
+    }
 }
@@ -4,13 +4,15 @@ use core::{fmt::Write, iter::Peekable, str::Chars};
 
 use ts_error::diagnostic::Span;
 
-use crate::parser::{Node, Value, Whitespace};
+use crate::parser::{Node, ParseOptions, Value, Whitespace};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// An array JSON value.
 pub struct Array {
     /// The span of the opening bracket.
     pub span: Span,
+    /// The span of the closing bracket.
+    pub closing_span: Span,
     /// The items in the array.
     pub items: Vec<Node>,
 }
@@ -18,27 +20,41 @@ pub struct Array {
 impl Array {
     /// Parse an array.
     pub fn parse(global_span: &mut Span, iter: &mut Peekable<Chars<'_>>) -> Option<Self> {
+        Self::parse_with(global_span, iter, ParseOptions::default())
+    }
+
+    /// Parse an array using some parse options.
+    pub fn parse_with(
+        global_span: &mut Span,
+        iter: &mut Peekable<Chars<'_>>,
+        options: ParseOptions,
+    ) -> Option<Self> {
         iter.next_if_eq(&'[')?;
         let span = global_span.length(1);
-        global_span.column += 1;
+        global_span.advance_columns(1);
 
         let mut items = Vec::new();
         while iter.peek().is_some_and(|character| *character != ']') {
             Whitespace::parse(global_span, iter);
 
-            if let Some(value) = Value::parse(global_span, iter) {
+            if let Some(value) = Value::parse_with(global_span, iter, options) {
                 items.push(Node { tag: None, value });
             };
 
             if iter.next_if_eq(&',').is_some() {
-                global_span.column += 1;
+                global_span.advance_columns(1);
             }
         }
 
+        let closing_span = global_span.length(1);
         iter.next_if_eq(&']')?;
-        global_span.column += 1;
+        global_span.advance_columns(1);
 
-        Some(Self { span, items })
+        Some(Self {
+            span,
+            closing_span,
+            items,
+        })
     }
 }
 
@@ -4,6 +4,26 @@ use core::{iter::Peekable, str::Chars};
 
 use ts_error::diagnostic::Span;
 
+use crate::parser::ParseOptions;
+
+/// The kind of a literal value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LiteralKind {
+    /// A number without a fraction or exponent.
+    Integer,
+    /// A number with a fraction or exponent.
+    Float,
+    /// `true` or `false`.
+    Bool,
+    /// `null`.
+    Null,
+    /// The non-standard `Infinity`, `-Infinity`, or `NaN`.
+    NonFinite,
+    /// Not a valid JSON literal.
+    Invalid,
+}
+
 /// A literal value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Literal {
@@ -15,6 +35,15 @@ pub struct Literal {
 impl Literal {
     /// Parse a literal.
     pub fn parse(global_span: &mut Span, iter: &mut Peekable<Chars<'_>>) -> Option<Self> {
+        Self::parse_with(global_span, iter, ParseOptions::default())
+    }
+
+    /// Parse a literal using some parse options.
+    pub fn parse_with(
+        global_span: &mut Span,
+        iter: &mut Peekable<Chars<'_>>,
+        options: ParseOptions,
+    ) -> Option<Self> {
         let mut span = global_span.length(0);
         let mut value = String::new();
 
@@ -24,10 +53,12 @@ impl Literal {
                 || "true".contains(*character)
                 || character.is_ascii_digit()
                 || matches!(character, '.' | '+' | 'e' | 'E' | '-')
+                || (options.allow_non_finite
+                    && ("Infinity".contains(*character) || "NaN".contains(*character)))
         }) {
             value.push(character);
             span.length += 1;
-            global_span.column += 1;
+            global_span.advance(character);
         }
 
         if value.is_empty() {
@@ -36,6 +67,54 @@ impl Literal {
 
         Some(Self { span, value })
     }
+
+    /// Classify the kind of the literal from its value.
+    pub fn classify(&self) -> LiteralKind {
+        match self.value.as_str() {
+            "true" | "false" => return LiteralKind::Bool,
+            "null" => return LiteralKind::Null,
+            "Infinity" | "-Infinity" | "NaN" => return LiteralKind::NonFinite,
+            _ => {}
+        }
+
+        // -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?
+        let mut iter = self.value.chars().peekable();
+        iter.next_if_eq(&'-');
+
+        match iter.next() {
+            Some('0') => {}
+            Some('1'..='9') => while iter.next_if(char::is_ascii_digit).is_some() {},
+            _ => return LiteralKind::Invalid,
+        }
+
+        let mut kind = LiteralKind::Integer;
+
+        if iter.next_if_eq(&'.').is_some() {
+            kind = LiteralKind::Float;
+            if iter.next_if(char::is_ascii_digit).is_none() {
+                return LiteralKind::Invalid;
+            }
+            while iter.next_if(char::is_ascii_digit).is_some() {}
+        }
+
+        if iter
+            .next_if(|character| matches!(character, 'e' | 'E'))
+            .is_some()
+        {
+            kind = LiteralKind::Float;
+            iter.next_if(|character| matches!(character, '+' | '-'));
+            if iter.next_if(char::is_ascii_digit).is_none() {
+                return LiteralKind::Invalid;
+            }
+            while iter.next_if(char::is_ascii_digit).is_some() {}
+        }
+
+        if iter.next().is_some() {
+            return LiteralKind::Invalid;
+        }
+
+        kind
+    }
 }
 
 impl core::fmt::Display for Literal {
@@ -43,3 +122,37 @@ impl core::fmt::Display for Literal {
         f.write_str(&self.value)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ts_error::diagnostic::Span;
+
+    use crate::parser::{Literal, LiteralKind};
+
+    #[test]
+    fn classifies_literals() {
+        let classify = |value: &str| {
+            Literal {
+                span: Span::default(),
+                value: value.to_string(),
+            }
+            .classify()
+        };
+
+        assert_eq!(LiteralKind::Float, classify("-1.04e2"));
+        assert_eq!(LiteralKind::Float, classify("0.5"));
+        assert_eq!(LiteralKind::Float, classify("1E+3"));
+        assert_eq!(LiteralKind::Integer, classify("42"));
+        assert_eq!(LiteralKind::Integer, classify("-0"));
+        assert_eq!(LiteralKind::Bool, classify("true"));
+        assert_eq!(LiteralKind::Bool, classify("false"));
+        assert_eq!(LiteralKind::Null, classify("null"));
+        assert_eq!(LiteralKind::NonFinite, classify("-Infinity"));
+        assert_eq!(LiteralKind::NonFinite, classify("NaN"));
+        assert_eq!(LiteralKind::Invalid, classify("1.2.3"));
+        assert_eq!(LiteralKind::Invalid, classify("01"));
+        assert_eq!(LiteralKind::Invalid, classify("1."));
+        assert_eq!(LiteralKind::Invalid, classify("1e"));
+        assert_eq!(LiteralKind::Invalid, classify("nul"));
+    }
+}
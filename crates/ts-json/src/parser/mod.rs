@@ -11,14 +11,29 @@ mod value;
 mod whitespace;
 
 use jsonschema::paths::{Location, LocationSegment};
-use ts_error::diagnostic::Span;
+use ts_error::diagnostic::{Context, Diagnostic, Span};
 
-pub(crate) use array::Array;
-pub(crate) use literal::Literal;
-pub(crate) use object::Object;
-pub(crate) use string::StringValue;
-pub(crate) use value::Value;
-pub(crate) use whitespace::Whitespace;
+pub use array::Array;
+pub use literal::{Literal, LiteralKind};
+pub use object::Object;
+pub use string::StringValue;
+pub use value::Value;
+pub use whitespace::Whitespace;
+
+/// Options for parsing a JSON document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Accept the non-standard `Infinity`, `-Infinity`, and `NaN` literals.
+    pub allow_non_finite: bool,
+}
+impl ParseOptions {
+    /// Accept the non-standard `Infinity`, `-Infinity`, and `NaN` literals.
+    pub fn allow_non_finite(mut self) -> Self {
+        self.allow_non_finite = true;
+        self
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// A JSON node, optional tag and a value.
@@ -30,14 +45,36 @@ pub struct Node {
 }
 
 impl Node {
-    /// Try parse a source document.
-    pub fn parse_document(source: &str) -> Option<Self> {
+    /// Try parse a source document, only whitespace may follow the root value.
+    pub fn parse_document(source: &str) -> Result<Self, Box<Diagnostic>> {
+        Self::parse_document_with(source, ParseOptions::default())
+    }
+
+    /// Try parse a source document using some parse options, only whitespace may follow the root
+    /// value.
+    pub fn parse_document_with(
+        source: &str,
+        options: ParseOptions,
+    ) -> Result<Self, Box<Diagnostic>> {
         let mut global_span = Span::default();
         let mut iter = source.chars().peekable();
 
-        let value = Value::parse(&mut global_span, &mut iter)?;
+        let Some(value) = Value::parse_with(&mut global_span, &mut iter, options) else {
+            return Err(Box::new(
+                Diagnostic::error("document is not valid JSON").at(source, global_span.length(1)),
+            ));
+        };
+
+        if iter.peek().is_some() {
+            return Err(Box::new(
+                Diagnostic::error("document has trailing content").context(
+                    Context::new(source, global_span.length(1))
+                        .label("expected the end of the document"),
+                ),
+            ));
+        }
 
-        Some(Self { tag: None, value })
+        Ok(Self { tag: None, value })
     }
 
     /// Try evaluate a pointer to the node it is pointing at.
@@ -63,6 +100,63 @@ impl Node {
     pub fn get<'a, 'b>(&'b self, index: Index<'a>) -> Option<&'b Self> {
         self.value.get(index)
     }
+
+    /// Returns the number of items in an array or properties in an object, or `None` for any other
+    /// value.
+    pub fn len(&self) -> Option<usize> {
+        match &self.value {
+            Value::Array(array) => Some(array.items.len()),
+            Value::Object(object) => Some(object.properties.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns if the node is an array or object without any items or properties.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Format the node with each item and property on its own line, indented by `indent` spaces
+    /// per level. Properties keep their source order.
+    pub fn to_pretty_string(&self, indent: usize) -> String {
+        let mut output = String::new();
+        self.write_pretty(&mut output, indent, 0);
+        output
+    }
+
+    /// Write the pretty formatted node to the output, see [`Self::to_pretty_string`].
+    fn write_pretty(&self, output: &mut String, indent: usize, depth: usize) {
+        if let Some(tag) = &self.tag {
+            output.push_str(&tag.to_string());
+            output.push_str(": ");
+        }
+
+        let children = match &self.value {
+            Value::Object(object) if !object.properties.is_empty() => {
+                Some(('{', &object.properties, '}'))
+            }
+            Value::Array(array) if !array.items.is_empty() => Some(('[', &array.items, ']')),
+            value => {
+                output.push_str(&value.to_string());
+                None
+            }
+        };
+
+        if let Some((open, children, close)) = children {
+            output.push(open);
+            output.push('\n');
+            for (index, child) in children.iter().enumerate() {
+                output.push_str(&" ".repeat(indent * (depth + 1)));
+                child.write_pretty(output, indent, depth + 1);
+                if index + 1 != children.len() {
+                    output.push(',');
+                }
+                output.push('\n');
+            }
+            output.push_str(&" ".repeat(indent * depth));
+            output.push(close);
+        }
+    }
 }
 
 impl core::fmt::Display for Node {
@@ -76,7 +170,9 @@ impl core::fmt::Display for Node {
 }
 
 /// An index into a JSON structure.
-pub(crate) enum Index<'a> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Index<'a> {
     /// Index an object by tag.
     Tag(&'a str),
     /// Index an array by index.
@@ -87,17 +183,24 @@ pub(crate) enum Index<'a> {
 mod test {
     use ts_error::diagnostic::Span;
 
-    use crate::parser::{Array, Literal, Node, Object, StringValue, Value};
+    use crate::parser::{
+        Array, Index, Literal, LiteralKind, Node, Object, ParseOptions, StringValue, Value,
+    };
 
     const SAMPLE: &str = include_str!("../../tests/sample.json");
 
-    fn object(span: Span, properties: Vec<Node>) -> Value {
-        Value::Object(Object { span, properties })
+    fn object(span: Span, closing_span: Span, properties: Vec<Node>) -> Value {
+        Value::Object(Object {
+            span,
+            closing_span,
+            properties,
+        })
     }
 
-    fn array(span: Span, items: Vec<Value>) -> Value {
+    fn array(span: Span, closing_span: Span, items: Vec<Value>) -> Value {
         Value::Array(Array {
             span,
+            closing_span,
             items: items
                 .into_iter()
                 .map(|value| Node { tag: None, value })
@@ -133,14 +236,17 @@ mod test {
     fn handles_sample() {
         let expected = object(
             Span::default(),
+            Span::default().line(14),
             vec![node(
                 Span::default().line(2).column(3).length(7),
                 "array",
                 array(
                     Span::default().line(2).column(12),
+                    Span::default().line(13).column(3),
                     vec![
                         object(
                             Span::default().line(3).column(5),
+                            Span::default().line(7).column(5),
                             vec![
                                 node(
                                     Span::default().line(4).column(7).length(6),
@@ -167,6 +273,7 @@ mod test {
                         ),
                         object(
                             Span::default().line(8).column(5),
+                            Span::default().line(12).column(5),
                             vec![
                                 node(
                                     Span::default().line(9).column(7).length(6),
@@ -196,4 +303,84 @@ mod test {
         let document = Node::parse_document(SAMPLE).expect("document should parse");
         assert_eq!(expected, document.value);
     }
+
+    #[test]
+    fn rejects_trailing_content() {
+        let diagnostic = Node::parse_document("{} oops").expect_err("document should not parse");
+        assert_eq!("document has trailing content", diagnostic.headline);
+
+        let context = diagnostic.context.expect("diagnostic should have context");
+        assert_eq!(Span::default().line(1).column(4).length(1), context.span);
+
+        let document = Node::parse_document("{}\n  \n").expect("document should parse");
+        assert_eq!(
+            object(Span::default(), Span::default().column(2), vec![]),
+            document.value
+        );
+    }
+
+    #[test]
+    fn counts_items() {
+        let document = Node::parse_document(SAMPLE).expect("document should parse");
+        assert_eq!(Some(1), document.len());
+
+        let array = document
+            .get(Index::Tag("array"))
+            .expect("array should exist");
+        assert_eq!(Some(2), array.len());
+
+        let object = array.get(Index::Index(0)).expect("item should exist");
+        assert_eq!(Some(3), object.len());
+
+        let flag = object.get(Index::Tag("flag")).expect("flag should exist");
+        assert_eq!(None, flag.len());
+        assert!(!flag.is_empty());
+
+        let document = Node::parse_document("[]").expect("document should parse");
+        assert!(document.is_empty());
+    }
+
+    #[test]
+    fn pretty_prints() {
+        let document = Node::parse_document(SAMPLE).expect("document should parse");
+        let pretty = document.to_pretty_string(2);
+        assert_eq!(SAMPLE.trim_end(), pretty);
+
+        let reformatted = Node::parse_document(&pretty)
+            .expect("formatted document should parse")
+            .to_pretty_string(2);
+        assert_eq!(pretty, reformatted);
+
+        let document = Node::parse_document(r#"{"a":[[1,[]],{}],"b":{ "c" : true }}"#)
+            .expect("document should parse");
+        assert_eq!(
+            "{\n    \"a\": [\n        [\n            1,\n            []\n        ],\n        {}\n    ],\n    \"b\": {\n        \"c\": true\n    }\n}",
+            document.to_pretty_string(4)
+        );
+    }
+
+    #[test]
+    fn parses_non_finite_literals() {
+        let source = r#"{"x": Infinity, "y": [-Infinity, NaN]}"#;
+        assert!(Node::parse_document(source).is_err());
+
+        let document =
+            Node::parse_document_with(source, ParseOptions::default().allow_non_finite())
+                .expect("document should parse");
+        let literal = |index: Index<'_>| match document.get(index).map(|node| &node.value) {
+            Some(Value::Literal(literal)) => literal.clone(),
+            value => panic!("expected a literal, found {value:?}"),
+        };
+
+        let x = literal(Index::Tag("x"));
+        assert_eq!("Infinity", x.value);
+        assert_eq!(Span::default().column(7).length(8), x.span);
+        assert_eq!(LiteralKind::NonFinite, x.classify());
+
+        let Some(Value::Array(y)) = document.get(Index::Tag("y")).map(|node| &node.value) else {
+            panic!("expected an array");
+        };
+        let values: Vec<_> = y.items.iter().map(ToString::to_string).collect();
+        assert_eq!(vec!["-Infinity", "NaN"], values);
+    }
 }
@@ -4,13 +4,15 @@ use core::{fmt::Write, iter::Peekable, str::Chars};
 
 use ts_error::diagnostic::Span;
 
-use crate::parser::{Node, StringValue, Value, Whitespace};
+use crate::parser::{Node, ParseOptions, StringValue, Value, Whitespace};
 
 /// A JSON object.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Object {
     /// The span of the opening brace.
     pub span: Span,
+    /// The span of the closing brace.
+    pub closing_span: Span,
     /// The child properties of the object.
     pub properties: Vec<Node>,
 }
@@ -18,9 +20,18 @@ pub struct Object {
 impl Object {
     /// Parse an object.
     pub fn parse(global_span: &mut Span, iter: &mut Peekable<Chars<'_>>) -> Option<Self> {
+        Self::parse_with(global_span, iter, ParseOptions::default())
+    }
+
+    /// Parse an object using some parse options.
+    pub fn parse_with(
+        global_span: &mut Span,
+        iter: &mut Peekable<Chars<'_>>,
+        options: ParseOptions,
+    ) -> Option<Self> {
         iter.next_if_eq(&'{')?;
         let span = global_span.length(1);
-        global_span.column += 1;
+        global_span.advance_columns(1);
 
         let mut properties = Vec::new();
 
@@ -31,9 +42,9 @@ impl Object {
 
             Whitespace::parse(global_span, iter);
             iter.next_if_eq(&':')?;
-            global_span.column += 1;
+            global_span.advance_columns(1);
 
-            if let Some(value) = Value::parse(global_span, iter) {
+            if let Some(value) = Value::parse_with(global_span, iter, options) {
                 properties.push(Node {
                     tag: Some(tag),
                     value,
@@ -41,14 +52,19 @@ impl Object {
             };
 
             if iter.next_if_eq(&',').is_some() {
-                global_span.column += 1;
+                global_span.advance_columns(1);
             }
         }
 
+        let closing_span = global_span.length(1);
         iter.next_if_eq(&'}')?;
-        global_span.column += 1;
+        global_span.advance_columns(1);
 
-        Some(Self { span, properties })
+        Some(Self {
+            span,
+            closing_span,
+            properties,
+        })
     }
 }
 
@@ -50,11 +50,70 @@ impl StringValue {
         }
 
         let columns = value.graphemes(true).count() + 2;
-        global_span.column += columns;
+        global_span.advance_columns(columns);
         span.length = columns;
 
         Some(Self { span, value })
     }
+
+    /// Returns the number of graphemes in the decoded value of the string.
+    pub fn grapheme_len(&self) -> usize {
+        self.decode().graphemes(true).count()
+    }
+
+    /// Returns the number of characters in the decoded value of the string.
+    pub fn char_len(&self) -> usize {
+        self.decode().chars().count()
+    }
+
+    /// Decode the escape sequences in the value of the string. Invalid escape sequences are
+    /// decoded as the replacement character.
+    fn decode(&self) -> String {
+        let mut decoded = String::with_capacity(self.value.len());
+        let mut chars = self.value.chars();
+
+        while let Some(character) = chars.next() {
+            if character != '\\' {
+                decoded.push(character);
+                continue;
+            }
+
+            match chars.next() {
+                Some('b') => decoded.push('\u{8}'),
+                Some('f') => decoded.push('\u{c}'),
+                Some('n') => decoded.push('\n'),
+                Some('r') => decoded.push('\r'),
+                Some('t') => decoded.push('\t'),
+                Some('u') => {
+                    let Some(high) = hex_escape(&mut chars) else {
+                        decoded.push(char::REPLACEMENT_CHARACTER);
+                        continue;
+                    };
+
+                    // Characters outside the basic multilingual plane are escaped as a surrogate
+                    // pair: `\uD83D\uDE00`
+                    let mut code = high;
+                    if (0xD800..0xDC00).contains(&high) {
+                        let mut lookahead = chars.clone();
+                        if lookahead.next() == Some('\\')
+                            && lookahead.next() == Some('u')
+                            && let Some(low) = hex_escape(&mut lookahead)
+                            && (0xDC00..0xE000).contains(&low)
+                        {
+                            chars = lookahead;
+                            code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                        }
+                    }
+
+                    decoded.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                Some(character) => decoded.push(character),
+                None => decoded.push('\\'),
+            }
+        }
+
+        decoded
+    }
 }
 
 impl core::fmt::Display for StringValue {
@@ -62,3 +121,70 @@ impl core::fmt::Display for StringValue {
         write!(f, "\"{}\"", self.value)
     }
 }
+
+/// Parse the four hex digits of a `\u` escape sequence.
+fn hex_escape(chars: &mut Chars<'_>) -> Option<u32> {
+    let digits: String = chars.by_ref().take(4).collect();
+    if digits.len() != 4 || !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use ts_ansi::style::{BOLD, RED, RESET};
+    use ts_error::diagnostic::{Context, Diagnostic, DisplayStyle, Span};
+
+    use crate::parser::StringValue;
+
+    #[test]
+    fn measures_decoded_value() {
+        let parse = |source: &str| {
+            StringValue::parse(&mut Span::default(), &mut source.chars().peekable())
+                .expect("string should parse")
+        };
+
+        // `e` followed by a combining acute accent
+        let string = parse("\"e\u{301}\"");
+        assert_eq!(1, string.grapheme_len());
+        assert_eq!(2, string.char_len());
+
+        let string = parse(r#""e\u0301\n\"\uD83D\uDE00""#);
+        assert_eq!(4, string.grapheme_len());
+        assert_eq!(5, string.char_len());
+        assert_eq!(23, string.value.chars().count());
+    }
+
+    #[test]
+    fn advances_across_combining_marks() {
+        // `e` followed by a combining acute accent is two characters, but a single grapheme.
+        let mut global_span = Span::default();
+        let string = StringValue::parse(&mut global_span, &mut "\"e\u{301}\"".chars().peekable())
+            .expect("string should parse");
+
+        assert_eq!(3, string.span.length);
+        assert_eq!(4, global_span.column);
+    }
+
+    #[test]
+    fn highlights_wide_graphemes() {
+        let source = "\"hi 🦀\"";
+        let mut global_span = Span::default();
+        let string = StringValue::parse(&mut global_span, &mut source.chars().peekable())
+            .expect("string should parse");
+
+        assert_eq!(6, string.span.length);
+        assert_eq!(7, global_span.column);
+
+        let output = Diagnostic::error("headline")
+            .context(Context::new(source, string.span))
+            .render(DisplayStyle::Color)
+            .to_string();
+        assert!(
+            output.contains(&format!("{RED}{BOLD}^^^^^^^{RESET}")),
+            "unexpected output {output:?}"
+        );
+    }
+}
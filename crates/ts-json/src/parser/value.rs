@@ -4,10 +4,11 @@ use core::{iter::Peekable, str::Chars};
 
 use ts_error::diagnostic::Span;
 
-use crate::parser::{Array, Index, Literal, Node, Object, StringValue, Whitespace};
+use crate::parser::{Array, Index, Literal, Node, Object, ParseOptions, StringValue, Whitespace};
 
 /// A JSON value.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Value {
     /// A string.
     String(StringValue),
@@ -22,13 +23,22 @@ pub enum Value {
 impl Value {
     /// Parse a value.
     pub fn parse(global_span: &mut Span, iter: &mut Peekable<Chars<'_>>) -> Option<Self> {
+        Self::parse_with(global_span, iter, ParseOptions::default())
+    }
+
+    /// Parse a value using some parse options.
+    pub fn parse_with(
+        global_span: &mut Span,
+        iter: &mut Peekable<Chars<'_>>,
+        options: ParseOptions,
+    ) -> Option<Self> {
         Whitespace::parse(global_span, iter);
 
         let value = match iter.peek()? {
             '\"' => Self::String(StringValue::parse(global_span, iter)?),
-            '{' => Self::Object(Object::parse(global_span, iter)?),
-            '[' => Self::Array(Array::parse(global_span, iter)?),
-            _ => Self::Literal(Literal::parse(global_span, iter)?),
+            '{' => Self::Object(Object::parse_with(global_span, iter, options)?),
+            '[' => Self::Array(Array::parse_with(global_span, iter, options)?),
+            _ => Self::Literal(Literal::parse_with(global_span, iter, options)?),
         };
 
         Whitespace::parse(global_span, iter);
@@ -71,6 +81,22 @@ impl Value {
             Self::Array(array) => array.span,
         }
     }
+
+    /// Get the span enclosing the whole value, from the opening to the closing bracket for arrays
+    /// and objects. Returns `None` if the value spans multiple lines.
+    pub fn enclosing_span(&self) -> Option<Span> {
+        let (opening_span, closing_span) = match &self {
+            Self::Object(object) => (object.span, object.closing_span),
+            Self::Array(array) => (array.span, array.closing_span),
+            _ => return Some(self.span()),
+        };
+
+        if opening_span.line != closing_span.line {
+            return None;
+        }
+
+        Some(opening_span.length(closing_span.column + closing_span.length - opening_span.column))
+    }
 }
 
 impl core::fmt::Display for Value {
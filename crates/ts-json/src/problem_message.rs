@@ -4,6 +4,7 @@ use jsonschema::{
     JsonType,
     error::{TypeKind, ValidationErrorKind},
 };
+use serde_json::Value;
 
 /// Extension trait for a [`ValidationErrorKind`].
 pub trait ProblemMessage {
@@ -41,19 +42,26 @@ impl ProblemMessage for ValidationErrorKind {
             )),
             Self::Custom { message } => Some(message.to_string()),
             Self::Enum { options } => Some(format!("this should be one of {options}")),
-            Self::ExclusiveMaximum { limit } => Some(format!("this should be less than {limit}")),
-            Self::ExclusiveMinimum { limit } => {
-                Some(format!("this should be greater then {limit}"))
+            Self::ExclusiveMaximum { limit } => {
+                Some(format!("this should be less than {}", display_limit(limit)))
             }
+            Self::ExclusiveMinimum { limit } => Some(format!(
+                "this should be greater then {}",
+                display_limit(limit)
+            )),
             Self::Format { format } => Some(format!("this should match the {format} format")),
             Self::MaxItems { limit } => Some(format!("this should have at most {limit} items")),
-            Self::Maximum { limit } => Some(format!("this should be at most {limit}")),
+            Self::Maximum { limit } => {
+                Some(format!("this should be at most {}", display_limit(limit)))
+            }
             Self::MaxLength { limit } => Some(format!("this should be most {limit} characters")),
             Self::MaxProperties { limit } => {
                 Some(format!("this should have at most {limit} properties"))
             }
             Self::MinItems { limit } => Some(format!("this should have at least {limit} items")),
-            Self::Minimum { limit } => Some(format!("this should be at least {limit}")),
+            Self::Minimum { limit } => {
+                Some(format!("this should be at least {}", display_limit(limit)))
+            }
             Self::MinLength { limit } => {
                 Some(format!("this should be at least {limit} characters"))
             }
@@ -130,6 +138,15 @@ impl ProblemMessage for ValidationErrorKind {
     }
 }
 
+/// Display a numeric limit, floats without a fractional part are displayed as integers:
+/// `65535` rather than `65535.0`.
+fn display_limit(limit: &Value) -> String {
+    match limit.as_f64() {
+        Some(float) if limit.is_f64() && float.is_finite() => float.to_string(),
+        _ => limit.to_string(),
+    }
+}
+
 /// Display a [`TypeKind`].
 fn display_type_kind(kind: &TypeKind) -> String {
     match kind {
@@ -157,3 +174,29 @@ fn display_json_type(json_type: JsonType) -> &'static str {
         JsonType::String => "a string",
     }
 }
+
+#[cfg(test)]
+mod test {
+    use jsonschema::error::ValidationErrorKind;
+    use serde_json::json;
+
+    use crate::problem_message::ProblemMessage;
+
+    #[test]
+    fn displays_integral_limits_as_integers() {
+        let message = |limit| ValidationErrorKind::Maximum { limit }.message();
+
+        assert_eq!(
+            Some("this should be at most 65535".to_string()),
+            message(json!(65535.0))
+        );
+        assert_eq!(
+            Some("this should be at most 65535".to_string()),
+            message(json!(65535))
+        );
+        assert_eq!(
+            Some("this should be at most 1.5".to_string()),
+            message(json!(1.5))
+        );
+    }
+}
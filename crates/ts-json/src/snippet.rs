@@ -0,0 +1,84 @@
+//! Extract the source text that a span points at.
+
+use core::iter;
+
+use ts_error::diagnostic::Span;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Returns the text of the source that a span covers, or `None` if the span is out of range.
+///
+/// Columns and lengths are counted in graphemes, so the spans from the [`parser`](crate::parser)
+/// can be used directly.
+pub fn span_text<'s>(source: &'s str, span: &Span) -> Option<&'s str> {
+    let start = byte_offset(source, span.line, span.column)?;
+    let end = match span.end {
+        Some((line, column)) => byte_offset(source, line, column + 1)?,
+        None => byte_offset(source, span.line, span.column + span.length)?,
+    };
+
+    source.get(start..end)
+}
+
+/// Returns the byte offset of a one-indexed line and column in the source. The column may be one
+/// past the last grapheme of the line.
+fn byte_offset(source: &str, line: usize, column: usize) -> Option<usize> {
+    let line_start = match line.checked_sub(1)? {
+        0 => 0,
+        index => source.match_indices('\n').nth(index - 1)?.0 + 1,
+    };
+
+    let line = source.get(line_start..)?.split('\n').next()?;
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    line.grapheme_indices(true)
+        .map(|(offset, _)| offset)
+        .chain(iter::once(line.len()))
+        .nth(column.checked_sub(1)?)
+        .map(|offset| line_start + offset)
+}
+
+#[cfg(test)]
+mod test {
+    use ts_error::diagnostic::Span;
+
+    use crate::{
+        parser::{Index, Node},
+        span_text,
+    };
+
+    const SOURCE: &str = include_str!("../tests/sample.json");
+
+    #[test]
+    fn extracts_span_text() {
+        let document = Node::parse_document(SOURCE).expect("sample should parse");
+        let text = document
+            .get(Index::Tag("array"))
+            .and_then(|node| node.get(Index::Index(0)))
+            .and_then(|node| node.get(Index::Tag("text")))
+            .expect("text should exist");
+
+        assert_eq!(
+            Some(r#""다람쥐 헌\n 쳇바퀴에 타고파""#),
+            span_text(SOURCE, &text.value.span())
+        );
+        assert_eq!(
+            Some(r#""text""#),
+            text.tag
+                .as_ref()
+                .and_then(|tag| span_text(SOURCE, &tag.span))
+        );
+
+        let span = Span::default().line(4).column(7).end(5, 12);
+        assert_eq!(
+            Some("\"text\": \"다람쥐 헌\\n 쳇바퀴에 타고파\",\n      \"flag\""),
+            span_text(SOURCE, &span)
+        );
+
+        assert_eq!(None, span_text(SOURCE, &Span::default().line(99)));
+        assert_eq!(None, span_text(SOURCE, &Span::default().line(0)));
+        assert_eq!(
+            None,
+            span_text(SOURCE, &Span::default().column(1).length(5))
+        );
+    }
+}
@@ -0,0 +1,849 @@
+//! A JSON schema validator that can be reused across many sources.
+
+use core::borrow::Borrow;
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use jsonschema::{ValidationOptions, error::ValidationErrorKind, paths::Location};
+use serde_json::Value;
+use ts_error::{
+    diagnostic::{Context, Diagnostic, Diagnostics, SourceIndex, Span},
+    normalize_message,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    ValidateTimings, ValidationError,
+    comments::strip_comments,
+    location::LocationExtensions,
+    parser::{Node, Value as SpannedValue},
+    problem_message::ProblemMessage,
+};
+
+/// The properties of an object that are missing or unexpected according to a schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ObjectReport {
+    /// The required properties the object is missing.
+    pub missing: Vec<String>,
+    /// The properties the object has that the schema does not allow.
+    pub unexpected: Vec<String>,
+}
+
+/// Options for validating JSON against a schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidateOptions {
+    /// Do not report properties that the schema does not allow, from `additionalProperties` or
+    /// `unevaluatedProperties`.
+    pub ignore_additional_properties: bool,
+}
+impl ValidateOptions {
+    /// Do not report properties that the schema does not allow, from `additionalProperties` or
+    /// `unevaluatedProperties`.
+    pub fn ignore_additional_properties(mut self) -> Self {
+        self.ignore_additional_properties = true;
+        self
+    }
+
+    /// Returns if a problem should be reported under these options.
+    fn reports(self, kind: &ValidationErrorKind) -> bool {
+        !(self.ignore_additional_properties
+            && matches!(
+                kind,
+                ValidationErrorKind::AdditionalProperties { .. }
+                    | ValidationErrorKind::UnevaluatedProperties { .. }
+            ))
+    }
+}
+
+/// A compiled JSON schema that sources can be validated against.
+pub struct Validator {
+    /// The compiled schema.
+    validator: jsonschema::Validator,
+    /// The schema, used to find descriptions for problems.
+    schema_node: Value,
+    /// The options for validating sources.
+    options: ValidateOptions,
+}
+
+impl Validator {
+    /// Compile a JSON schema into a validator.
+    pub fn new(schema: &str) -> Result<Self, ValidationError> {
+        let schema_node: Value = serde_json::from_str(schema)
+            .map_err(|source| ValidationError::ParseSchema { source })?;
+
+        Self::from_value(schema_node)
+    }
+
+    /// Compile a JSON schema that has already been parsed into a validator.
+    pub fn from_value(schema_node: Value) -> Result<Self, ValidationError> {
+        let validator = ValidationOptions::default()
+            .build(&schema_node)
+            .map_err(|source| ValidationError::CreateValidator {
+                source: Box::new(source),
+            })?;
+
+        Ok(Self {
+            validator,
+            schema_node,
+            options: ValidateOptions::default(),
+        })
+    }
+
+    /// Sets the options for validating sources.
+    pub fn options(mut self, options: ValidateOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Validate some JSON against the schema, returning all problems.
+    ///
+    /// A trailing comma in the JSON is reported as an error diagnostic, rather than failing.
+    pub fn validate(
+        &self,
+        source: &str,
+        source_path: Option<&Path>,
+    ) -> Result<Diagnostics, ValidationError> {
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        validate_source(&mut diagnostics, source, source_path, || Ok(self), false)?;
+
+        Ok(diagnostics)
+    }
+
+    /// Validate many sources against the schema, returning all problems grouped by source.
+    ///
+    /// Sources that are not valid JSON are reported as an error diagnostic.
+    pub fn validate_many(&self, sources: &[(PathBuf, String)]) -> Diagnostics {
+        let mut diagnostics = Diagnostics::new("validating JSON");
+
+        for (path, source) in sources {
+            match serde_json::from_str::<Value>(source) {
+                Ok(source_node) => {
+                    self.push_problems(&mut diagnostics, source, &source_node, Some(path));
+                }
+                Err(error) => {
+                    diagnostics.push(
+                        syntax_diagnostic(&error, source, "source file is not valid JSON")
+                            .file_path(path.display()),
+                    );
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Validate JSON that was merged from many sources against the schema, returning all problems.
+    ///
+    /// `origin` returns the index into `sources` of the source that defined the value at a JSON
+    /// pointer, each problem is located in that source. The sources may contain comments, see
+    /// [`strip_comments`].
+    pub fn validate_merged<F>(
+        &self,
+        merged: &Value,
+        sources: &[(PathBuf, String)],
+        origin: F,
+    ) -> Diagnostics
+    where
+        F: Fn(&str) -> Option<usize>,
+    {
+        let mut diagnostics = Diagnostics::new("validating JSON");
+
+        let documents: Vec<_> = sources
+            .iter()
+            .map(|(path, source)| {
+                let document = Node::parse_document(&strip_comments(source)).ok();
+                (path, SourceIndex::new(source), document)
+            })
+            .collect();
+
+        for error in self
+            .validator
+            .iter_errors(merged)
+            .filter(|error| self.options.reports(&error.kind))
+        {
+            let diagnostic =
+                match origin(error.instance_path.as_str()).and_then(|index| documents.get(index)) {
+                    Some((path, index, document)) => self.diagnostic(
+                        &error,
+                        index,
+                        locate_problem(&error, document.as_ref()),
+                        Some(path),
+                    ),
+                    None => self.diagnostic(&error, &SourceIndex::new(""), None, None),
+                };
+            diagnostics.push(diagnostic);
+        }
+
+        diagnostics
+    }
+
+    /// Analyze the object at a JSON pointer in some JSON, returning the required properties it is
+    /// missing and the properties it has that are not allowed.
+    pub fn analyze_object(
+        &self,
+        source: &str,
+        pointer: &str,
+    ) -> Result<ObjectReport, ValidationError> {
+        let source_node: Value = serde_json::from_str(source)
+            .map_err(|source| ValidationError::ParseSource { source })?;
+
+        let mut report = ObjectReport::default();
+        for error in self.validator.iter_errors(&source_node) {
+            if error.instance_path.as_str() != pointer {
+                continue;
+            }
+
+            match &error.kind {
+                ValidationErrorKind::Required { property } => {
+                    let property = property
+                        .as_str()
+                        .map_or_else(|| property.to_string(), ToString::to_string);
+                    if !report.missing.contains(&property) {
+                        report.missing.push(property);
+                    }
+                }
+                ValidationErrorKind::AdditionalProperties { unexpected }
+                | ValidationErrorKind::UnevaluatedProperties { unexpected } => {
+                    for property in unexpected {
+                        if !report.unexpected.contains(property) {
+                            report.unexpected.push(property.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Returns the JSON pointers of the optional properties defined by the schema that are not set
+    /// in some JSON, in sorted order.
+    ///
+    /// Properties are found from the `properties` of the schema, and of the subschemas of the
+    /// properties that are set. Required properties are not included, validation reports them.
+    pub fn unset_optional_properties(&self, source: &str) -> Result<Vec<String>, ValidationError> {
+        let source_node: Value = serde_json::from_str(source)
+            .map_err(|source| ValidationError::ParseSource { source })?;
+
+        let mut unset = Vec::new();
+        push_unset_properties(&mut unset, &self.schema_node, &source_node, "");
+        unset.sort();
+
+        Ok(unset)
+    }
+
+    /// Push the problems with a parsed source into the diagnostics.
+    pub(crate) fn push_problems(
+        &self,
+        diagnostics: &mut Diagnostics,
+        source: &str,
+        source_node: &Value,
+        source_path: Option<&Path>,
+    ) {
+        if self.validator.is_valid(source_node) {
+            return;
+        }
+
+        let document = Node::parse_document(source).ok();
+        self.push_located_problems(
+            diagnostics,
+            &SourceIndex::new(source),
+            source_node,
+            source_path,
+            |error| locate_problem(error, document.as_ref()),
+        );
+    }
+
+    /// Push the problems with a parsed source into the diagnostics, where `locate` returns the
+    /// span of the source a problem is with.
+    pub(crate) fn push_located_problems<F>(
+        &self,
+        diagnostics: &mut Diagnostics,
+        index: &SourceIndex<'_>,
+        source_node: &Value,
+        source_path: Option<&Path>,
+        locate: F,
+    ) where
+        F: Fn(&jsonschema::ValidationError<'_>) -> Option<Span>,
+    {
+        for error in self
+            .validator
+            .iter_errors(source_node)
+            .filter(|error| self.options.reports(&error.kind))
+        {
+            diagnostics.push(self.diagnostic(&error, index, locate(&error), source_path));
+        }
+    }
+
+    /// Validate some JSON against the schema, returning only the first problem, if there is one.
+    pub fn validate_first(
+        &self,
+        source: &str,
+        source_path: Option<&Path>,
+    ) -> Result<Option<Diagnostic>, ValidationError> {
+        let mut diagnostics = Diagnostics::new("validating JSON");
+        validate_source(&mut diagnostics, source, source_path, || Ok(self), true)?;
+
+        Ok(diagnostics.problems.into_iter().next())
+    }
+
+    /// Returns the first problem with a parsed source, if there is one.
+    fn first_problem(
+        &self,
+        source: &str,
+        source_node: &Value,
+        source_path: Option<&Path>,
+    ) -> Option<Diagnostic> {
+        let error = self
+            .validator
+            .iter_errors(source_node)
+            .find(|error| self.options.reports(&error.kind))?;
+
+        let document = Node::parse_document(source).ok();
+        Some(self.diagnostic(
+            &error,
+            &SourceIndex::new(source),
+            locate_problem(&error, document.as_ref()),
+            source_path,
+        ))
+    }
+
+    /// Create a diagnostic for a validation problem, with the description then the examples from the
+    /// schema as notes.
+    fn diagnostic(
+        &self,
+        error: &jsonschema::ValidationError<'_>,
+        index: &SourceIndex<'_>,
+        span: Option<Span>,
+        source_path: Option<&Path>,
+    ) -> Diagnostic {
+        let mut diagnostic = problem_diagnostic(error, index, span);
+        diagnostic.file_path = source_path.map(|path| path.display().to_string());
+
+        if let Some(parent) = error.schema_path.parent() {
+            diagnostic.merge_notes_from(self.schema_notes(&parent));
+        }
+
+        diagnostic
+    }
+
+    /// Returns the notes for a subschema, the lines of its description followed by its examples.
+    fn schema_notes(&self, location: &Location) -> Vec<String> {
+        let mut notes = Vec::new();
+
+        if let Some(contents) = self
+            .schema_node
+            .pointer(location.join("description").as_str())
+            .and_then(Value::as_str)
+        {
+            notes.extend(contents.lines().map(normalize_message));
+        }
+
+        if let Some(examples) = self
+            .schema_node
+            .pointer(location.join("examples").as_str())
+            .and_then(Value::as_array)
+        {
+            notes.extend(examples.iter().map(|example| format!("example: {example}")));
+        }
+
+        notes
+    }
+}
+
+/// Validate some JSON, pushing its problems into the diagnostics, and returning how long each step
+/// took. The validator is only compiled by `compile` once the source has parsed.
+///
+/// A trailing comma in the JSON is pushed as an error diagnostic, rather than failing. If
+/// `first_only` is set, only the first problem is pushed.
+pub(crate) fn validate_source<V: Borrow<Validator>>(
+    diagnostics: &mut Diagnostics,
+    source: &str,
+    source_path: Option<&Path>,
+    compile: impl FnOnce() -> Result<V, ValidationError>,
+    first_only: bool,
+) -> Result<ValidateTimings, ValidationError> {
+    let mut timings = ValidateTimings::default();
+
+    let started = Instant::now();
+    let source_node = serde_json::from_str::<Value>(source);
+    timings.parse = started.elapsed();
+
+    let source_node = match source_node {
+        Ok(source_node) => source_node,
+        Err(error) => {
+            let diagnostic = trailing_comma_diagnostic(&error, source, source_path)
+                .ok_or(ValidationError::ParseSource { source: error })?;
+            diagnostics.push(diagnostic);
+            return Ok(timings);
+        }
+    };
+
+    let started = Instant::now();
+    let validator = compile()?;
+    timings.compile = started.elapsed();
+
+    let started = Instant::now();
+    let validator = validator.borrow();
+    if first_only {
+        if let Some(problem) = validator.first_problem(source, &source_node, source_path) {
+            diagnostics.push(problem);
+        }
+    } else {
+        validator.push_problems(diagnostics, source, &source_node, source_path);
+    }
+    timings.validate = started.elapsed();
+
+    Ok(timings)
+}
+
+/// Returns the span of a parsed JSON document that a validation problem is with, if it can be found.
+pub(crate) fn locate_problem(
+    error: &jsonschema::ValidationError<'_>,
+    document: Option<&Node>,
+) -> Option<Span> {
+    let node = document?.evaluate(&error.instance_path)?;
+
+    let span = match node.value {
+        SpannedValue::Array(_) | SpannedValue::Object(_) => {
+            if (node.is_empty() || is_container_problem(&error.kind))
+                && let Some(span) = node.value.enclosing_span()
+            {
+                span
+            } else if let Some(tag) = &node.tag {
+                tag.span
+            } else {
+                node.value.span()
+            }
+        }
+        _ => node.value.span(),
+    };
+
+    Some(span)
+}
+
+/// Create a diagnostic for a validation problem, with context if the span of the source the problem
+/// is with is known.
+pub(crate) fn problem_diagnostic(
+    error: &jsonschema::ValidationError<'_>,
+    index: &SourceIndex<'_>,
+    span: Option<Span>,
+) -> Diagnostic {
+    let context = span.map(|span| {
+        let mut context = Context::from_index(index, span);
+        context.label = error.kind.message();
+        context
+    });
+
+    let mut diagnostic = Diagnostic::error(format!(
+        "`{}` {}",
+        error.instance_path,
+        error.kind.headline()
+    ));
+    diagnostic.context = context;
+
+    diagnostic
+}
+
+/// Create a diagnostic for a source that is not valid JSON, locating the problem in the source.
+///
+/// The `headline` is the message of the diagnostic, e.g., `"source file is not valid JSON"`.
+pub fn syntax_diagnostic(error: &serde_json::Error, source: &str, headline: &str) -> Diagnostic {
+    if let Some(diagnostic) = trailing_comma_diagnostic(error, source, None) {
+        return diagnostic;
+    }
+
+    let span = Span::default()
+        .line(error.line().max(1))
+        .column(error.column().max(1));
+
+    Diagnostic::error(headline)
+        .at(source, span)
+        .add_note(normalize_message(error))
+}
+
+/// Create a diagnostic for a source that is not valid JSON because of a trailing comma, located at
+/// the comma. Returns `None` if the error is not from a trailing comma.
+///
+/// Trailing commas are a common mistake, and `serde_json` locates them at the closing bracket
+/// rather than the comma.
+pub(crate) fn trailing_comma_diagnostic(
+    error: &serde_json::Error,
+    source: &str,
+    source_path: Option<&Path>,
+) -> Option<Diagnostic> {
+    if !error.is_syntax() || !error.to_string().starts_with("trailing comma") {
+        return None;
+    }
+
+    let span = trailing_comma_span(source)?;
+    let mut diagnostic = Diagnostic::error("trailing comma is not allowed in JSON")
+        .context(Context::new(source, span).label("remove this comma"));
+    diagnostic.file_path = source_path.map(|path| path.display().to_string());
+
+    Some(diagnostic)
+}
+
+/// Returns the span of the first comma in a source that is followed only by whitespace before a
+/// closing bracket.
+fn trailing_comma_span(source: &str) -> Option<Span> {
+    let mut span = Span::default();
+    let mut comma = None;
+    let mut in_string = false;
+    let mut is_escaped = false;
+
+    for grapheme in source.graphemes(true) {
+        if in_string {
+            if is_escaped {
+                is_escaped = false;
+            } else if grapheme == "\\" {
+                is_escaped = true;
+            } else if grapheme == "\"" {
+                in_string = false;
+            }
+        } else {
+            match grapheme {
+                "," => comma = Some(span),
+                "}" | "]" if comma.is_some() => return comma,
+                _ if grapheme.trim().is_empty() => {}
+                _ => {
+                    in_string = grapheme == "\"";
+                    comma = None;
+                }
+            }
+        }
+
+        if grapheme.contains('\n') {
+            span.advance('\n');
+        } else if grapheme != "\r" {
+            span.advance_columns(1);
+        }
+    }
+
+    None
+}
+
+/// Returns if the problem is with an array or object as a whole, rather than with a property or item.
+fn is_container_problem(kind: &ValidationErrorKind) -> bool {
+    matches!(
+        kind,
+        ValidationErrorKind::AdditionalItems { .. }
+            | ValidationErrorKind::AdditionalProperties { .. }
+            | ValidationErrorKind::Contains
+            | ValidationErrorKind::MaxItems { .. }
+            | ValidationErrorKind::MaxProperties { .. }
+            | ValidationErrorKind::MinItems { .. }
+            | ValidationErrorKind::MinProperties { .. }
+            | ValidationErrorKind::Required { .. }
+            | ValidationErrorKind::UniqueItems
+            | ValidationErrorKind::UnevaluatedItems { .. }
+            | ValidationErrorKind::UnevaluatedProperties { .. }
+    )
+}
+
+/// Push the JSON pointers of the optional properties of a schema that are not set in an instance.
+fn push_unset_properties(unset: &mut Vec<String>, schema: &Value, instance: &Value, pointer: &str) {
+    let (Some(properties), Some(object)) = (
+        schema.get("properties").and_then(Value::as_object),
+        instance.as_object(),
+    ) else {
+        return;
+    };
+
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    for (name, subschema) in properties {
+        let property_pointer = format!("{pointer}/{}", name.replace('~', "~0").replace('/', "~1"));
+
+        match object.get(name) {
+            Some(value) => push_unset_properties(unset, subschema, value, &property_pointer),
+            None if !required.contains(&name.as_str()) => unset.push(property_pointer),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use ts_error::diagnostic::Span;
+
+    use crate::{ObjectReport, ValidateOptions, Validator};
+
+    const SCHEMA: &str = r#"{ "type": "object", "required": ["a"] }"#;
+
+    #[test]
+    fn validates_many() {
+        let validator = Validator::new(SCHEMA).expect("schema should compile");
+
+        let sources = [
+            (PathBuf::from("valid.json"), r#"{ "a": 1 }"#.to_string()),
+            (PathBuf::from("missing.json"), r#"{ "b": 1 }"#.to_string()),
+            (PathBuf::from("malformed.json"), r#"{ "a": "#.to_string()),
+        ];
+
+        let diagnostics = validator.validate_many(&sources);
+        assert_eq!(2, diagnostics.errors().count());
+
+        let paths: Vec<_> = diagnostics
+            .problems
+            .iter()
+            .map(|problem| problem.file_path.as_deref())
+            .collect();
+        assert_eq!(vec![Some("missing.json"), Some("malformed.json")], paths);
+
+        let diagnostics = validator
+            .validate(r#"{ "a": 1 }"#, Some(Path::new("valid.json")))
+            .expect("validation to succeed");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn locates_merged_problems_in_their_origin() {
+        let validator = Validator::new(
+            r#"{ "type": "object", "properties": { "b": { "type": "integer" } }, "required": ["a"] }"#,
+        )
+        .expect("schema should compile");
+
+        let sources = [
+            (
+                PathBuf::from("base.json"),
+                "{\n  \"b\": \"two\"\n}".to_string(),
+            ),
+            (PathBuf::from("config.json"), "// no `a`\n{}".to_string()),
+        ];
+        let merged = serde_json::json!({ "b": "two" });
+
+        let diagnostics = validator.validate_merged(&merged, &sources, |pointer| match pointer {
+            "/b" => Some(0),
+            _ => Some(1),
+        });
+        assert_eq!(2, diagnostics.errors().count());
+
+        let locations: Vec<_> = diagnostics
+            .problems
+            .iter()
+            .map(|problem| {
+                (
+                    problem.file_path.as_deref(),
+                    problem.context.as_ref().map(|context| context.span.line),
+                )
+            })
+            .collect();
+        assert!(locations.contains(&(Some("base.json"), Some(2))));
+        assert!(locations.contains(&(Some("config.json"), Some(2))));
+    }
+
+    #[test]
+    fn compiles_parsed_schema() {
+        let schema_node = serde_json::from_str(SCHEMA).expect("schema should parse");
+        let validator = Validator::from_value(schema_node).expect("schema should compile");
+
+        let diagnostics = validator
+            .validate(r#"{ "b": 1 }"#, None)
+            .expect("validation to succeed");
+        assert_eq!(
+            Validator::new(SCHEMA)
+                .expect("schema should compile")
+                .validate(r#"{ "b": 1 }"#, None)
+                .expect("validation to succeed")
+                .to_string(),
+            diagnostics.to_string()
+        );
+        assert_eq!(1, diagnostics.errors().count());
+    }
+
+    #[test]
+    fn spans_whole_container() {
+        let validator =
+            Validator::new(r#"{ "properties": { "list": { "type": "array", "minItems": 3 } } }"#)
+                .expect("schema should compile");
+
+        let diagnostics = validator
+            .validate(r#"{ "list": [1, 2] }"#, None)
+            .expect("validation to succeed");
+        let context = diagnostics
+            .problems
+            .first()
+            .and_then(|problem| problem.context.as_ref())
+            .expect("problem should have context");
+        assert_eq!(Span::default().column(11).length(6), context.span);
+    }
+
+    #[test]
+    fn spans_empty_container() {
+        let validator = Validator::new(
+            r#"{ "minProperties": 1, "properties": { "a": { "type": "string" } } }"#,
+        )
+        .expect("schema should compile");
+
+        let diagnostics = validator
+            .validate("{}", None)
+            .expect("validation to succeed");
+        let problem = diagnostics
+            .problems
+            .first()
+            .expect("there should be a problem");
+        let context = problem
+            .context
+            .as_ref()
+            .expect("problem should have context");
+        assert_eq!(Span::default().length(2), context.span);
+        let output = problem.to_string();
+        assert!(output.contains("^^ ") && !output.contains("^^^"));
+
+        let diagnostics = validator
+            .validate(r#"{ "a": {} }"#, None)
+            .expect("validation to succeed");
+        let context = diagnostics
+            .problems
+            .first()
+            .and_then(|problem| problem.context.as_ref())
+            .expect("problem should have context");
+        assert_eq!(Span::default().column(8).length(2), context.span);
+    }
+
+    #[test]
+    fn analyzes_object() {
+        let validator = Validator::new(
+            r#"{
+  "properties": {
+    "server": {
+      "type": "object",
+      "required": ["host", "port"],
+      "properties": { "host": { "type": "string" }, "port": { "type": "integer" } },
+      "additionalProperties": false
+    }
+  }
+}"#,
+        )
+        .expect("schema should compile");
+
+        let report = validator
+            .analyze_object(
+                r#"{ "server": { "port": 80, "hots": "localhost", "tls": true } }"#,
+                "/server",
+            )
+            .expect("analysis should succeed");
+        assert_eq!(
+            ObjectReport {
+                missing: vec!["host".to_string()],
+                unexpected: vec!["hots".to_string(), "tls".to_string()],
+            },
+            report
+        );
+
+        let report = validator
+            .analyze_object(r#"{ "server": { "port": 80 } }"#, "")
+            .expect("analysis should succeed");
+        assert_eq!(ObjectReport::default(), report);
+    }
+
+    #[test]
+    fn orders_schema_notes() {
+        let validator = Validator::new(
+            r#"{
+  "properties": {
+    "port": {
+      "description": "The port.\nThe port.",
+      "examples": [8080, 8080, 443],
+      "type": "integer"
+    }
+  }
+}"#,
+        )
+        .expect("schema should compile");
+
+        let diagnostics = validator
+            .validate(r#"{ "port": "eighty" }"#, None)
+            .expect("validation to succeed");
+        let problem = diagnostics
+            .problems
+            .first()
+            .expect("there should be a problem");
+        assert_eq!(
+            vec!["the port", "example: 8080", "example: 443"],
+            problem.notes
+        );
+    }
+
+    #[test]
+    fn finds_unset_optional_properties() {
+        let validator = Validator::new(
+            r#"{
+  "required": ["name"],
+  "properties": {
+    "name": { "type": "string" },
+    "log_level": { "type": "string" },
+    "server": {
+      "type": "object",
+      "required": ["port"],
+      "properties": { "host": { "type": "string" }, "port": { "type": "integer" } }
+    },
+    "cache": {
+      "type": "object",
+      "properties": { "size": { "type": "integer" } }
+    }
+  }
+}"#,
+        )
+        .expect("schema should compile");
+
+        let unset = validator
+            .unset_optional_properties(r#"{ "server": {} }"#)
+            .expect("source should parse");
+        assert_eq!(vec!["/cache", "/log_level", "/server/host"], unset);
+
+        let unset = validator
+            .unset_optional_properties(
+                r#"{ "name": "a", "log_level": "info", "server": { "host": "b" }, "cache": {} }"#,
+            )
+            .expect("source should parse");
+        assert_eq!(vec!["/cache/size"], unset);
+    }
+
+    #[test]
+    fn ignores_additional_properties() {
+        let schema = r#"{
+  "type": "object",
+  "properties": { "name": { "type": "string" } },
+  "additionalProperties": false
+}"#;
+        let source = r#"{ "name": 5, "legacy": true }"#;
+
+        let headlines = |validator: Validator| {
+            validator
+                .validate(source, None)
+                .expect("validation to succeed")
+                .problems
+                .into_iter()
+                .map(|problem| problem.headline)
+                .collect::<Vec<_>>()
+        };
+
+        let validator = Validator::new(schema).expect("schema should compile");
+        assert_eq!(2, headlines(validator).len());
+
+        let validator = Validator::new(schema)
+            .expect("schema should compile")
+            .options(ValidateOptions::default().ignore_additional_properties());
+        assert_eq!(vec!["`/name` is the wrong type"], headlines(validator));
+
+        let first = Validator::new(schema)
+            .expect("schema should compile")
+            .options(ValidateOptions::default().ignore_additional_properties())
+            .validate_first(r#"{ "name": "a", "legacy": true }"#, None)
+            .expect("validation to succeed");
+        assert!(first.is_none());
+    }
+}
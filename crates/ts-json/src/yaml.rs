@@ -0,0 +1,247 @@
+//! Validating YAML by converting it to JSON.
+
+use std::{collections::HashMap, path::Path};
+
+use serde_json::{Map, Number, Value};
+use ts_error::diagnostic::{Diagnostics, SourceIndex, Span};
+use unicode_segmentation::UnicodeSegmentation;
+use yaml_rust2::{
+    Yaml,
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::{Marker, TScalarStyle},
+};
+
+use crate::{ValidationError, Validator};
+
+/// A YAML collection that is being built.
+enum Collection {
+    /// A sequence and the items so far.
+    Sequence {
+        /// The JSON pointer to the sequence.
+        pointer: String,
+        /// The anchor of the sequence, zero if it has none.
+        anchor: usize,
+        /// The items of the sequence so far.
+        items: Vec<Value>,
+    },
+    /// A mapping, the entries so far, and the key of the next value.
+    Mapping {
+        /// The JSON pointer to the mapping.
+        pointer: String,
+        /// The anchor of the mapping, zero if it has none.
+        anchor: usize,
+        /// The entries of the mapping so far.
+        entries: Map<String, Value>,
+        /// The key of the next value, if the key has been read.
+        key: Option<String>,
+    },
+}
+
+/// Builds a JSON value from the events of a YAML parser, recording where each value is in the
+/// YAML source.
+#[derive(Default)]
+struct YamlDocument {
+    /// The collections that are being built, innermost last.
+    collections: Vec<Collection>,
+    /// The value of the document once it has been built.
+    root: Option<Value>,
+    /// The span of each value in the source, keyed by its JSON pointer.
+    spans: HashMap<String, Span>,
+    /// The value of each anchor, so aliases can be resolved.
+    anchors: HashMap<usize, Value>,
+}
+impl YamlDocument {
+    /// Returns the JSON pointer of the next value, or `None` if the next value is a mapping key.
+    fn next_pointer(&self) -> Option<String> {
+        match self.collections.last() {
+            None => Some(String::new()),
+            Some(Collection::Sequence { pointer, items, .. }) => {
+                Some(format!("{pointer}/{}", items.len()))
+            }
+            Some(Collection::Mapping { key: None, .. }) => None,
+            Some(Collection::Mapping {
+                pointer,
+                key: Some(key),
+                ..
+            }) => Some(format!(
+                "{pointer}/{}",
+                key.replace('~', "~0").replace('/', "~1")
+            )),
+        }
+    }
+
+    /// Add a complete value to the collection it is in.
+    fn insert(&mut self, value: Value, anchor: usize) {
+        if anchor != 0 {
+            self.anchors.insert(anchor, value.clone());
+        }
+
+        match self.collections.last_mut() {
+            None => self.root = Some(value),
+            Some(Collection::Sequence { items, .. }) => items.push(value),
+            Some(Collection::Mapping { entries, key, .. }) => match key.take() {
+                Some(key) => {
+                    entries.insert(key, value);
+                }
+                None => {
+                    *key = Some(match value {
+                        Value::String(string) => string,
+                        value => value.to_string(),
+                    });
+                }
+            },
+        }
+    }
+}
+impl MarkedEventReceiver for YamlDocument {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let pointer = self.next_pointer();
+        let span = Span::at(mark.line(), mark.col() + 1);
+
+        match event {
+            Event::Scalar(scalar, style, anchor, _) => {
+                if let Some(pointer) = pointer {
+                    let quotes = match style {
+                        TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted => 2,
+                        _ => 0,
+                    };
+                    self.spans.insert(
+                        pointer,
+                        span.length((scalar.graphemes(true).count() + quotes).max(1)),
+                    );
+                }
+
+                let value = if style == TScalarStyle::Plain {
+                    scalar_value(&Yaml::from_str(&scalar))
+                } else {
+                    Value::String(scalar)
+                };
+                self.insert(value, anchor);
+            }
+            Event::Alias(anchor) => {
+                if let Some(pointer) = pointer {
+                    self.spans.insert(pointer, span);
+                }
+                let value = self.anchors.get(&anchor).cloned().unwrap_or(Value::Null);
+                self.insert(value, 0);
+            }
+            Event::SequenceStart(anchor, _) => {
+                let pointer = pointer.unwrap_or_default();
+                self.spans.insert(pointer.clone(), span);
+                self.collections.push(Collection::Sequence {
+                    pointer,
+                    anchor,
+                    items: Vec::new(),
+                });
+            }
+            Event::MappingStart(anchor, _) => {
+                let pointer = pointer.unwrap_or_default();
+                self.spans.insert(pointer.clone(), span);
+                self.collections.push(Collection::Mapping {
+                    pointer,
+                    anchor,
+                    entries: Map::new(),
+                    key: None,
+                });
+            }
+            Event::SequenceEnd | Event::MappingEnd => match self.collections.pop() {
+                Some(Collection::Sequence { anchor, items, .. }) => {
+                    self.insert(Value::Array(items), anchor);
+                }
+                Some(Collection::Mapping {
+                    anchor, entries, ..
+                }) => {
+                    self.insert(Value::Object(entries), anchor);
+                }
+                None => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Validate some YAML against a JSON schema, returning all problems located in the YAML source.
+///
+/// The YAML is converted to JSON before it is validated, only the first document in the source is
+/// validated.
+pub fn validate_yaml(
+    source: &str,
+    schema: &str,
+    source_path: Option<&Path>,
+) -> Result<Diagnostics, ValidationError> {
+    let validator = Validator::new(schema)?;
+
+    let mut document = YamlDocument::default();
+    Parser::new_from_str(source)
+        .load(&mut document, false)
+        .map_err(|source| ValidationError::ParseYaml { source })?;
+    let source_node = document.root.take().unwrap_or(Value::Null);
+
+    let mut diagnostics = Diagnostics::new("validating YAML");
+    validator.push_located_problems(
+        &mut diagnostics,
+        &SourceIndex::new(source),
+        &source_node,
+        source_path,
+        |error| document.spans.get(error.instance_path.as_str()).copied(),
+    );
+
+    Ok(diagnostics)
+}
+
+/// Convert a resolved plain YAML scalar to a JSON value.
+fn scalar_value(scalar: &Yaml) -> Value {
+    match scalar {
+        Yaml::Boolean(boolean) => Value::Bool(*boolean),
+        Yaml::Integer(integer) => Value::Number(Number::from(*integer)),
+        Yaml::Real(real) => scalar
+            .as_f64()
+            .and_then(Number::from_f64)
+            .map_or_else(|| Value::String(real.clone()), Value::Number),
+        Yaml::String(string) => Value::String(string.clone()),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use ts_error::diagnostic::Span;
+
+    use crate::validate_yaml;
+
+    #[test]
+    fn locates_problems_in_yaml() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "ports": { "type": "array", "items": { "type": "integer" } }
+            },
+            "required": ["name"]
+        })
+        .to_string();
+        let source = "name: server\nports:\n  - 80\n  - \"443\"\n";
+
+        let diagnostics = validate_yaml(source, &schema, None).expect("validating should succeed");
+        assert_eq!(1, diagnostics.problems.len());
+
+        let context = diagnostics
+            .problems
+            .first()
+            .and_then(|diagnostic| diagnostic.context.as_ref())
+            .expect("the problem should be located");
+        assert_eq!(Span::at(4, 5).length(5), context.span);
+
+        let diagnostics =
+            validate_yaml("ports: []", &schema, None).expect("validating should succeed");
+        let context = diagnostics
+            .problems
+            .first()
+            .and_then(|diagnostic| diagnostic.context.as_ref())
+            .expect("the problem should be located");
+        assert_eq!(1, context.span.line);
+
+        assert!(validate_yaml("name: [", &schema, None).is_err());
+    }
+}
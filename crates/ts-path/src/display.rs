@@ -48,11 +48,25 @@ pub fn display_path(path: &Path) -> String {
         .join(separator)
 }
 
+/// Display a path relative to `root` if the path is under `root`, otherwise display the path as
+/// is, see [`display_path`].
+/// * Both paths are normalised before comparing.
+/// * A path that is `root` is displayed as `.`.
+pub fn strip_prefix_display(path: &Path, root: &Path) -> String {
+    let path = path.normalized();
+    let root = root.normalized();
+
+    match path.strip_prefix(&root) {
+        Ok(relative) => display_path(relative),
+        Err(_) => display_path(&path),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::path::Path;
 
-    use crate::display::DisplayPath;
+    use crate::display::{DisplayPath, strip_prefix_display};
 
     #[test]
     fn handles_relative() {
@@ -86,4 +100,21 @@ mod test {
         let data = Path::new(r"\some\absolute\path\2");
         assert_eq!(expected, data.opinionated_display());
     }
+
+    #[test]
+    fn strips_prefix() {
+        let root = Path::new("some/project");
+
+        let data = Path::new("some/project/src/../src/lib.rs");
+        assert_eq!("src/lib.rs", strip_prefix_display(data, root));
+
+        let data = Path::new("some/other/src/lib.rs");
+        assert_eq!("some/other/src/lib.rs", strip_prefix_display(data, root));
+
+        let data = Path::new("some/project-two/lib.rs");
+        assert_eq!("some/project-two/lib.rs", strip_prefix_display(data, root));
+
+        let data = Path::new("some/project/");
+        assert_eq!(".", strip_prefix_display(data, root));
+    }
 }
@@ -0,0 +1,78 @@
+//! Simple glob matching for file names.
+
+/// Returns if a value matches a glob pattern.
+///
+/// * `*` matches any sequence of characters, including an empty sequence.
+/// * `?` matches any single character.
+/// * All other characters must match exactly.
+pub fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    matches(&pattern, &value)
+}
+
+/// Returns if the value matches the pattern.
+///
+/// Only the most recent `*` is backtracked to, extending what it matches by one character at a
+/// time, so matching takes at most `pattern.len() * value.len()` steps.
+fn matches(pattern: &[char], value: &[char]) -> bool {
+    let mut pattern_index = 0;
+    let mut value_index = 0;
+    // The position in the pattern after the most recent `*`, and where in the value it matches to.
+    let mut backtrack = None;
+
+    while let Some(value_char) = value.get(value_index) {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                pattern_index += 1;
+                backtrack = Some((pattern_index, value_index));
+            }
+            Some(pattern_char) if *pattern_char == '?' || pattern_char == value_char => {
+                pattern_index += 1;
+                value_index += 1;
+            }
+            _ => {
+                let Some((star_pattern_index, star_value_index)) = backtrack else {
+                    return false;
+                };
+                pattern_index = star_pattern_index;
+                value_index = star_value_index + 1;
+                backtrack = Some((star_pattern_index, value_index));
+            }
+        }
+    }
+
+    pattern
+        .get(pattern_index..)
+        .is_some_and(|rest| rest.iter().all(|pattern_char| *pattern_char == '*'))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::glob_match;
+
+    #[test]
+    fn handles_wildcards() {
+        assert!(glob_match("*.json", "config.json"));
+        assert!(glob_match("*.json", ".json"));
+        assert!(!glob_match("*.json", "config.jsonc"));
+        assert!(glob_match("config.*", "config.json"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("c?nfig.json", "config.json"));
+        assert!(!glob_match("c?nfig.json", "cnfig.json"));
+        assert!(glob_match("node_modules", "node_modules"));
+        assert!(!glob_match("node_modules", "node_module"));
+        assert!(glob_match("다람*", "다람쥐"));
+        assert!(glob_match("*a*b", "xaxxb"));
+        assert!(!glob_match("*a*b", "xaxxbc"));
+        assert!(glob_match("**", ""));
+        assert!(!glob_match("?", ""));
+    }
+
+    #[test]
+    fn matches_many_wildcards_quickly() {
+        let pattern = "*a".repeat(32);
+        let value = "a".repeat(64) + "b";
+        assert!(!glob_match(&pattern, &value));
+    }
+}
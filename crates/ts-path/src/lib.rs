@@ -5,9 +5,15 @@
 extern crate alloc;
 
 mod display;
+mod glob;
 mod normalize;
 mod relative;
+mod stem;
+mod walk;
 
-pub use display::{DisplayPath, display_path};
+pub use display::{DisplayPath, display_path, strip_prefix_display};
+pub use glob::glob_match;
 pub use normalize::{NormalizePath, normalize_path};
 pub use relative::{RelativePath, relative_path};
+pub use stem::{first_existing, has_stem};
+pub use walk::{WalkMatching, WalkMatchingError, walk_matching};
@@ -49,6 +49,8 @@ impl<'a> From<Component<'a>> for CustomComponent<'a> {
                 }
                 Prefix::VerbatimDisk(disk) | Prefix::Disk(disk) => {
                     let mut prefix = OsString::with_capacity(2);
+                    // Windows convention is uppercase drive letters, `c:` and `C:` are the same disk.
+                    let disk = disk.to_ascii_uppercase();
                     let letter = str::from_utf8(slice::from_ref(&disk)).unwrap_or("C");
                     prefix.push(letter);
                     prefix.push(":");
@@ -92,7 +94,7 @@ impl<P: AsRef<Path>> NormalizePath for P {
 /// Normalize a path using only the components of the path.
 ///
 /// This will ignore any symbolic links, and strip the verbatim `\\?\` prefixes, so should only be
-/// used when that can be tolerated.
+/// used when that can be tolerated. Drive letters are uppercased.
 pub fn normalize_path(path: &Path) -> PathBuf {
     let mut output: Vec<CustomComponent> = Vec::with_capacity(path.components().count());
 
@@ -156,6 +158,17 @@ mod test {
         assert_eq!(data, data.normalized());
     }
 
+    #[test]
+    #[cfg(windows)]
+    fn handles_drive_case() {
+        use crate::RelativePath;
+
+        let lower = Path::new(r"c:\a");
+        let upper = Path::new(r"C:\a");
+        assert_eq!(upper.normalized(), lower.normalized());
+        assert_eq!(Path::new("."), lower.relative_to(upper));
+    }
+
     #[test]
     fn handles_parent() {
         let expected = Path::new(r"../../path");
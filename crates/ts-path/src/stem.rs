@@ -0,0 +1,67 @@
+//! Find files by their stem, regardless of their extension.
+
+use std::path::{Path, PathBuf};
+
+/// Returns if the file stem of a path matches `stem`, regardless of the path's extension.
+///
+/// On Windows the stem is compared case-insensitively.
+pub fn has_stem(path: &Path, stem: &str) -> bool {
+    let Some(file_stem) = path.file_stem().and_then(|file_stem| file_stem.to_str()) else {
+        return false;
+    };
+
+    if cfg!(windows) {
+        file_stem.to_lowercase() == stem.to_lowercase()
+    } else {
+        file_stem == stem
+    }
+}
+
+/// Returns the first candidate path that exists.
+pub fn first_existing(candidates: &[PathBuf]) -> Option<PathBuf> {
+    candidates
+        .iter()
+        .find(|candidate| candidate.exists())
+        .cloned()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::Path};
+
+    use crate::{first_existing, has_stem};
+
+    #[test]
+    fn matches_stem() {
+        assert!(has_stem(Path::new("config.json"), "config"));
+        assert!(has_stem(Path::new("dir/config.yaml"), "config"));
+        assert!(has_stem(Path::new("config"), "config"));
+        assert!(has_stem(Path::new("config.local.toml"), "config.local"));
+        assert!(!has_stem(Path::new("config.json"), "config.json"));
+        assert!(!has_stem(Path::new("configs.json"), "config"));
+        assert!(!has_stem(Path::new(""), "config"));
+        assert_eq!(cfg!(windows), has_stem(Path::new("Config.json"), "config"));
+    }
+
+    #[test]
+    fn finds_first_existing() {
+        let directory = env::temp_dir().join("ts-path-first-existing");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).expect("creating the directory should succeed");
+        fs::write(directory.join("config.yaml"), "").expect("writing the file should succeed");
+        fs::write(directory.join("config.toml"), "").expect("writing the file should succeed");
+
+        let candidates = ["config.json", "config.yaml", "config.toml"]
+            .map(|name| directory.join(name))
+            .to_vec();
+
+        assert_eq!(
+            Some(directory.join("config.yaml")),
+            first_existing(&candidates)
+        );
+        assert_eq!(None, first_existing(&[directory.join("config.json")]));
+        assert_eq!(None, first_existing(&[]));
+
+        fs::remove_dir_all(&directory).expect("removing directory should succeed");
+    }
+}
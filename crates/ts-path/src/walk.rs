@@ -0,0 +1,182 @@
+//! Recursively find the files in a directory that match a glob pattern.
+
+use std::{
+    fs::{self, ReadDir},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{DisplayPath, glob_match};
+
+/// Error variants for walking a directory.
+#[derive(Debug)]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub enum WalkMatchingError {
+    #[non_exhaustive]
+    ReadDirectory { path: PathBuf, source: io::Error },
+
+    #[non_exhaustive]
+    ReadEntry { path: PathBuf, source: io::Error },
+}
+impl core::fmt::Display for WalkMatchingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self {
+            Self::ReadDirectory { path, .. } => {
+                write!(
+                    f,
+                    "could not read directory `{}`",
+                    path.opinionated_display()
+                )
+            }
+            Self::ReadEntry { path, .. } => {
+                write!(
+                    f,
+                    "could not read an entry of directory `{}`",
+                    path.opinionated_display()
+                )
+            }
+        }
+    }
+}
+impl core::error::Error for WalkMatchingError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self {
+            Self::ReadDirectory { source, .. } | Self::ReadEntry { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Iterator over the files in a directory that match a glob pattern, see [`walk_matching`].
+#[derive(Debug)]
+pub struct WalkMatching {
+    /// The glob pattern files must match.
+    pattern: String,
+    /// The glob patterns of directories to skip.
+    ignore: Vec<String>,
+    /// The directories that are yet to be read.
+    directories: Vec<PathBuf>,
+    /// The directory currently being read.
+    current: Option<(PathBuf, ReadDir)>,
+}
+
+impl Iterator for WalkMatching {
+    type Item = Result<PathBuf, WalkMatchingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some((directory, entries)) = &mut self.current else {
+                let directory = self.directories.pop()?;
+                match fs::read_dir(&directory) {
+                    Ok(entries) => self.current = Some((directory, entries)),
+                    Err(source) => {
+                        return Some(Err(WalkMatchingError::ReadDirectory {
+                            path: directory,
+                            source,
+                        }));
+                    }
+                }
+                continue;
+            };
+
+            let entry = match entries.next() {
+                Some(Ok(entry)) => entry,
+                Some(Err(source)) => {
+                    return Some(Err(WalkMatchingError::ReadEntry {
+                        path: directory.clone(),
+                        source,
+                    }));
+                }
+                None => {
+                    self.current = None;
+                    continue;
+                }
+            };
+
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                if !self.ignore.iter().any(|ignore| glob_match(ignore, &name)) {
+                    self.directories.push(path);
+                }
+                continue;
+            }
+
+            let is_file = if file_type.is_symlink() {
+                fs::metadata(&path).is_ok_and(|metadata| !metadata.is_dir())
+            } else {
+                true
+            };
+
+            if is_file && glob_match(&self.pattern, &name) {
+                return Some(Ok(path));
+            }
+        }
+    }
+}
+
+/// Recursively find the files in `root` whose names match the glob `pattern`, skipping any
+/// directories whose names match a glob in `ignore`.
+///
+/// * Symbolic links to directories are not followed.
+/// * Directories that cannot be read are skipped, yielding an error so the caller can warn.
+/// * Entries of a directory that cannot be read yield an error, the rest of the directory is
+///   still walked.
+pub fn walk_matching(root: &Path, pattern: &str, ignore: &[&str]) -> WalkMatching {
+    WalkMatching {
+        pattern: pattern.to_string(),
+        ignore: ignore.iter().map(ToString::to_string).collect(),
+        directories: vec![root.to_path_buf()],
+        current: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::walk_matching;
+
+    #[test]
+    fn skips_ignored_directories() {
+        let root = env::temp_dir().join("ts-path-walk-matching");
+        let _ = fs::remove_dir_all(&root);
+
+        for directory in ["sub/nested", "target/debug", "node_modules/package", ".git"] {
+            fs::create_dir_all(root.join(directory)).expect("creating directory should succeed");
+        }
+        for file in [
+            "a.json",
+            "b.txt",
+            "sub/c.json",
+            "sub/nested/d.json",
+            "target/debug/e.json",
+            "node_modules/package/f.json",
+            ".git/g.json",
+        ] {
+            fs::write(root.join(file), "{}").expect("writing file should succeed");
+        }
+
+        let mut files: Vec<PathBuf> =
+            walk_matching(&root, "*.json", &["target", "node_modules", ".git"])
+                .collect::<Result<_, _>>()
+                .expect("walking should succeed");
+        files.sort();
+
+        fs::remove_dir_all(&root).expect("removing directory should succeed");
+
+        assert_eq!(
+            vec![
+                root.join("a.json"),
+                root.join("sub/c.json"),
+                root.join("sub/nested/d.json"),
+            ],
+            files
+        );
+    }
+}
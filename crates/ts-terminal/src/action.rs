@@ -1,9 +1,33 @@
 //! Reporting an action that a CLI is performing.
 
-use alloc::string::{String, ToString};
-use std::io::{Write, stderr};
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+};
+use core::time::Duration;
+use std::{
+    io::{IsTerminal, Write, stderr},
+    sync::{
+        Mutex, MutexGuard, PoisonError,
+        mpsc::{self, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
 
-use ts_ansi::style::*;
+use ts_ansi::{style::*, truncate_visible, visible_width};
+
+use crate::{ansi_support::styles_stderr, terminal_size};
+
+/// The width of the terminal in columns when it is not known.
+const DEFAULT_TERMINAL_WIDTH: usize = 40;
+
+/// The frames of the spinner shown while a ticked action is in progress.
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// How often a ticker thread advances the spinner of an action.
+const TICK_INTERVAL: Duration = Duration::from_millis(80);
 
 /// Extension trait to update an action state based on the value of `self`.
 pub trait ActionResult {
@@ -47,16 +71,46 @@ impl<T> ActionResult for Option<T> {
     }
 }
 
+/// Extension trait to update an action state based on the value of `self`, where an empty value
+/// means there was nothing to do, so the action is reported as skipped rather than failed.
+pub trait SkippableActionResult {
+    /// Bind the final outcome of the action to the state of `self`, reporting the action as
+    /// skipped if `self` is empty.
+    fn bind_action_or_skip(self, action: Action) -> Self;
+}
+
+impl<T> SkippableActionResult for Option<T> {
+    fn bind_action_or_skip(self, mut action: Action) -> Self {
+        match &self {
+            Some(_) => action.report_success(),
+            None => action.report_skipped(),
+        }
+        self
+    }
+}
+impl<T, E> SkippableActionResult for Result<Option<T>, E> {
+    fn bind_action_or_skip(self, mut action: Action) -> Self {
+        match &self {
+            Ok(Some(_)) => action.report_success(),
+            Ok(None) => action.report_skipped(),
+            Err(_) => action.report_fail(),
+        }
+        self
+    }
+}
+
 /// Action State
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
-enum ActionState {
+pub(crate) enum ActionState {
     /// The action is in progress.
     InProgress,
     /// The action was a success.
     Success,
     /// The action was an error.
     Fail,
+    /// The action was not performed, e.g., there was nothing to do.
+    Skipped,
 }
 
 /// Action progress reporter.
@@ -72,6 +126,24 @@ pub struct Action {
     detail: String,
     /// Should the action erase the previous line when printing the next state.
     should_erase: bool,
+    /// Should the final success or failure line be kept when printing the next state.
+    persist_completed: bool,
+    /// Is `stderr` a terminal, if not, only the final state is printed.
+    is_terminal: bool,
+    /// Should output to `stderr` be styled, if not, only the final state is printed without
+    /// styling.
+    is_styled: bool,
+    /// The frame of the spinner, if the action has been ticked.
+    spinner_frame: Option<usize>,
+    /// Should the time the action took be shown when it is completed.
+    show_timing: bool,
+    /// When the action started.
+    started: Instant,
+    /// How long the action took, once it is completed.
+    elapsed: Option<Duration>,
+    /// The number of items that are done and the total number of items, if the action tracks
+    /// progress.
+    progress: Option<(u64, u64)>,
 }
 
 impl Action {
@@ -80,19 +152,19 @@ impl Action {
     /// ## Limitations
     /// * Anything else writing to the `stdout`/`stderr` will cause this to erase them unless
     ///   [`Self::dont_erase`] is called.
-    /// * If the content is wrapped, this will erase part of it, keep details and verbs short.
+    /// * In progress lines are truncated to the width of the terminal so they can be erased, keep
+    ///   details and verbs short.
+    /// * Actions reporting from different threads will erase each other's lines, use an
+    ///   [`ActionArea`](crate::ActionArea) to give each thread its own line.
+    /// * If `stderr` is not a terminal, only the final success or failure is printed.
+    /// * If `stderr` does not process ANSI escape sequences, or styling is disabled, only the final
+    ///   success or failure is printed, without styling. See [`ts_ansi::should_style`].
     pub fn new<S1: ToString, S2: ToString, S3: ToString>(
         actioning_verb: S1,
         actioned_verb: S2,
         detail: S3,
     ) -> Self {
-        let mut progress = Self {
-            state: ActionState::InProgress,
-            actioning_verb: actioning_verb.to_string(),
-            actioned_verb: actioned_verb.to_string(),
-            detail: detail.to_string(),
-            should_erase: false,
-        };
+        let mut progress = Self::unreported(actioning_verb, actioned_verb, detail, styles_stderr());
 
         progress.print();
         progress
@@ -100,58 +172,598 @@ impl Action {
 
     /// Report the action as failed.
     pub fn report_fail(&mut self) {
-        self.state = ActionState::Fail;
+        self.set_state(ActionState::Fail);
         self.print();
     }
 
     /// Report the action as a success.
     pub fn report_success(&mut self) {
-        self.state = ActionState::Success;
+        self.set_state(ActionState::Success);
+        self.print();
+    }
+
+    /// Report the action as skipped, e.g., the result was cached or there was nothing to do.
+    pub fn report_skipped(&mut self) {
+        self.set_state(ActionState::Skipped);
+        self.print();
+    }
+
+    /// Set the number of items that are done and reprint the progress bar, see
+    /// [`Self::with_total`].
+    ///
+    /// All IO errors are ignored.
+    pub fn set_progress(&mut self, done: u64) {
+        let Some((_, total)) = self.progress else {
+            return;
+        };
+
+        self.progress = Some((done.min(total), total));
         self.print();
     }
 
+    /// Advance the spinner of an in progress action and reprint it.
+    ///
+    /// All IO errors are ignored.
+    pub fn tick(&mut self) {
+        let mut stderr = stderr().lock();
+        self.tick_to(&mut stderr);
+    }
+
     /// Print the message for this action to `stderr`.
     ///
     /// All IO errors are ignored.
     pub fn print(&mut self) {
+        let mut stderr = stderr().lock();
+        self.write(&mut stderr);
+    }
+
+    /// Write the message for this action to a writer.
+    ///
+    /// All IO errors are ignored.
+    fn write<W: Write>(&mut self, writer: &mut W) {
         #![expect(
             unused_must_use,
             reason = "displaying output is a non-critical part of the program, so this should not 
             panic, additionally, I don't want to have to think about the errors when calling this"
         )]
 
-        let mut stderr = stderr().lock();
+        let is_interactive = self.is_terminal && self.is_styled;
 
-        if self.should_erase {
-            stderr.write_all(ERASE_LINE_UP.as_bytes());
+        if !is_interactive && self.state == ActionState::InProgress {
+            return;
         }
 
+        if self.should_erase && is_interactive {
+            writer.write_all(ERASE_LINE_UP.as_bytes());
+        }
+
+        writeln!(writer, "{}", self.message());
+        writer.flush();
+
+        self.should_erase = !(self.persist_completed && self.state != ActionState::InProgress);
+    }
+
+    /// Disable erasing the previous line on next print.
+    pub fn dont_erase(&mut self) {
+        self.should_erase = false;
+    }
+
+    /// Returns the message for the current state of this action, without a trailing newline.
+    pub(crate) fn message(&self) -> String {
+        self.message_within(terminal_width())
+    }
+
+    /// Returns the message for the current state of this action, without a trailing newline. An in
+    /// progress message is truncated to `width` columns, so it is redrawn on a single line.
+    fn message_within(&self, width: usize) -> String {
         let actioning = &self.actioning_verb;
         let actioned = &self.actioned_verb;
         let detail = &self.detail;
+        let spinner = self
+            .spinner_frame
+            .and_then(|frame| SPINNER_FRAMES.get(frame))
+            .filter(|_| self.is_styled);
+        let progress = match self.progress {
+            Some((done, total)) => {
+                // `{spinner} {actioning} {detail} {bar}`
+                let prefix_width = spinner.map_or(0, |spinner| visible_width(spinner) + 1)
+                    + visible_width(actioning)
+                    + visible_width(detail)
+                    + 2;
+                let bar = progress_bar(done, total, width.saturating_sub(prefix_width));
+                if bar.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {bar}")
+                }
+            }
+            None => String::new(),
+        };
+        let timing = match self.elapsed {
+            Some(elapsed) if self.show_timing => format!(" ({})", humanize(elapsed)),
+            _ => String::new(),
+        };
 
-        match self.state {
-            ActionState::InProgress => {
-                writeln!(stderr, "{CYAN}{BOLD}{actioning}{RESET} {detail}");
+        if !self.is_styled {
+            match self.state {
+                ActionState::InProgress => {
+                    truncate_visible(&format!("{actioning} {detail}{progress}"), width)
+                }
+                ActionState::Success => format!("{actioned} {detail}{timing}"),
+                ActionState::Fail => format!("{actioning} {detail} failed{timing}"),
+                ActionState::Skipped => format!("{actioning} {detail} skipped{timing}"),
             }
-            ActionState::Success => {
-                writeln!(stderr, "{GREEN}{BOLD}{actioned}{RESET} {detail}");
+        } else {
+            match self.state {
+                ActionState::InProgress => {
+                    let message = match spinner {
+                        Some(spinner) => {
+                            format!("{CYAN}{BOLD}{spinner} {actioning}{RESET} {detail}{progress}")
+                        }
+                        None => format!("{CYAN}{BOLD}{actioning}{RESET} {detail}{progress}"),
+                    };
+                    truncate_visible(&message, width)
+                }
+                ActionState::Success => {
+                    format!("{GREEN}{BOLD}{actioned}{RESET} {detail}{timing}")
+                }
+                ActionState::Fail => format!(
+                    "{RED}{BOLD}{actioning}{RESET} {detail} {RED}{BOLD}failed{RESET}{timing}"
+                ),
+                ActionState::Skipped => {
+                    format!("{DIM}{actioning} {detail} skipped{timing}{RESET}")
+                }
             }
-            ActionState::Fail => {
-                writeln!(
-                    stderr,
-                    "{RED}{BOLD}{actioning}{RESET} {detail} {RED}{BOLD}failed{RESET}"
-                );
+        }
+    }
+
+    /// Keep the final success or failure line on screen as a permanent log line, only the in
+    /// progress line is erased when printing the next state.
+    pub fn persist_completed(mut self) -> Self {
+        self.persist_completed = true;
+        self
+    }
+
+    /// Set the state of this action without reporting it, completing the action records how long
+    /// it took.
+    pub(crate) fn set_state(&mut self, state: ActionState) {
+        self.state = state;
+        self.elapsed = match state {
+            ActionState::InProgress => None,
+            ActionState::Success | ActionState::Fail | ActionState::Skipped => {
+                Some(self.started.elapsed())
             }
         };
+    }
+
+    /// Drive the spinner of this action from a background thread, ticking it at a fixed interval
+    /// until the action is reported through the returned handle.
+    ///
+    /// The action is shared with the ticker thread behind a lock, so the ticker and the handle never
+    /// write to `stderr` at the same time. The cursor is hidden while the spinner is driven.
+    pub fn spawn_ticker(self) -> ActionHandle {
+        let hides_cursor = self.is_terminal && self.is_styled;
+        if hides_cursor {
+            let _ = stderr().lock().write_all(HIDE_CURSOR.as_bytes());
+        }
+
+        let action = Arc::new(Mutex::new(self));
+        let (stop, stopped) = mpsc::channel::<()>();
+        let ticker = {
+            let action = Arc::clone(&action);
+            thread::spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(TICK_INTERVAL) {
+                    action.lock().unwrap_or_else(PoisonError::into_inner).tick();
+                }
+            })
+        };
 
-        stderr.flush();
+        ActionHandle {
+            action,
+            hides_cursor,
+            ticker: Some((stop, ticker)),
+        }
+    }
 
-        self.should_erase = true;
+    /// Returns the current state of this action.
+    pub(crate) fn state(&self) -> ActionState {
+        self.state
     }
 
-    /// Disable erasing the previous line on next print.
-    pub fn dont_erase(&mut self) {
-        self.should_erase = false;
+    /// Advance the spinner of an in progress action and write it to a writer.
+    fn tick_to<W: Write>(&mut self, writer: &mut W) {
+        if self.state != ActionState::InProgress {
+            return;
+        }
+
+        self.spinner_frame = Some(
+            self.spinner_frame
+                .map_or(0, |frame| (frame + 1) % SPINNER_FRAMES.len()),
+        );
+        self.write(writer);
+    }
+
+    /// Create an action without reporting it.
+    pub(crate) fn unreported<S1: ToString, S2: ToString, S3: ToString>(
+        actioning_verb: S1,
+        actioned_verb: S2,
+        detail: S3,
+        is_styled: bool,
+    ) -> Self {
+        Self {
+            state: ActionState::InProgress,
+            actioning_verb: actioning_verb.to_string(),
+            actioned_verb: actioned_verb.to_string(),
+            detail: detail.to_string(),
+            should_erase: false,
+            persist_completed: false,
+            is_terminal: stderr().is_terminal(),
+            is_styled,
+            spinner_frame: None,
+            show_timing: false,
+            started: Instant::now(),
+            elapsed: None,
+            progress: None,
+        }
+    }
+
+    /// Track the progress of the action over `total` items, showing a progress bar sized to the
+    /// terminal while the action is in progress, e.g., `[####----] 50% (5/10)`.
+    ///
+    /// The bar is cleared when the action is completed.
+    pub fn with_total(mut self, total: u64) -> Self {
+        self.progress = Some((0, total));
+        self
+    }
+
+    /// Show how long the action took when it is completed, e.g., `Built project (1.3s)`.
+    pub fn with_timing(mut self) -> Self {
+        self.show_timing = true;
+        self
+    }
+}
+
+/// An action whose spinner is driven by a ticker thread, see [`Action::spawn_ticker`].
+///
+/// The ticker is stopped when the action is reported, or when the handle is dropped, and the cursor
+/// is shown again.
+pub struct ActionHandle {
+    /// The action, shared with the ticker thread.
+    action: Arc<Mutex<Action>>,
+    /// Was the cursor hidden when the ticker was spawned.
+    hides_cursor: bool,
+    /// The sender to stop the ticker thread, and the thread, while it is running.
+    ticker: Option<(Sender<()>, JoinHandle<()>)>,
+}
+impl ActionHandle {
+    /// Lock the action.
+    fn lock(&self) -> MutexGuard<'_, Action> {
+        self.action.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Stop the ticker and report the action as failed.
+    pub fn report_fail(mut self) {
+        self.stop();
+        self.lock().report_fail();
+    }
+
+    /// Stop the ticker and report the action as skipped.
+    pub fn report_skipped(mut self) {
+        self.stop();
+        self.lock().report_skipped();
+    }
+
+    /// Stop the ticker and report the action as a success.
+    pub fn report_success(mut self) {
+        self.stop();
+        self.lock().report_success();
+    }
+
+    /// Stop the ticker thread and wait for it to finish.
+    fn stop(&mut self) {
+        if let Some((stop, ticker)) = self.ticker.take() {
+            drop(stop);
+            let _ = ticker.join();
+        }
+    }
+}
+impl Drop for ActionHandle {
+    fn drop(&mut self) {
+        self.stop();
+
+        if self.hides_cursor {
+            let _ = stderr().lock().write_all(SHOW_CURSOR.as_bytes());
+        }
+    }
+}
+
+/// Returns a progress bar that fits in `width` columns, e.g., `[####----] 50% (5/10)`.
+///
+/// If there is no room for the bar, only the percentage and counts are returned, if there is no
+/// room for those either, an empty string is returned.
+fn progress_bar(done: u64, total: u64, width: usize) -> String {
+    let percent = if total == 0 {
+        100
+    } else {
+        u128::from(done.min(total)) * 100 / u128::from(total)
+    };
+    let counts = format!("{percent}% ({done}/{total})");
+    if counts.len() > width {
+        return String::new();
+    }
+
+    let cells = width.saturating_sub(counts.len() + 3);
+    if cells == 0 {
+        return counts;
+    }
+
+    let filled = usize::try_from(u128::try_from(cells).unwrap_or(u128::MAX) * percent / 100)
+        .unwrap_or(cells)
+        .min(cells);
+    format!(
+        "[{}{}] {counts}",
+        "#".repeat(filled),
+        "-".repeat(cells - filled)
+    )
+}
+
+/// Returns the width of the terminal in columns, or [`DEFAULT_TERMINAL_WIDTH`] if it is not known.
+fn terminal_width() -> usize {
+    terminal_size::terminal_width().unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// Format a duration for display, e.g., `250ms`, `1.3s`, or `1m04s`.
+fn humanize(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1000 {
+        return format!("{millis}ms");
+    }
+
+    let tenths = millis / 100;
+    if tenths < 600 {
+        return format!("{}.{}s", tenths / 10, tenths % 10);
+    }
+
+    let seconds = duration.as_secs();
+    format!("{}m{:02}s", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, sync::Arc};
+    use core::time::Duration;
+    use std::{thread, time::Instant};
+
+    use ts_ansi::{style::*, visible_width};
+
+    use crate::action::{Action, ActionState, TICK_INTERVAL, humanize, progress_bar};
+
+    fn action(is_terminal: bool, is_styled: bool) -> Action {
+        Action {
+            state: ActionState::InProgress,
+            actioning_verb: "Building".to_string(),
+            actioned_verb: "Built".to_string(),
+            detail: "project".to_string(),
+            should_erase: false,
+            persist_completed: false,
+            is_terminal,
+            is_styled,
+            spinner_frame: None,
+            show_timing: false,
+            started: Instant::now(),
+            elapsed: None,
+            progress: None,
+        }
+    }
+
+    #[test]
+    fn only_writes_final_state_when_not_terminal() {
+        let mut output = Vec::new();
+        let mut action = action(false, true);
+        action.write(&mut output);
+        action.state = ActionState::Success;
+        action.write(&mut output);
+
+        assert_eq!(
+            format!("{GREEN}{BOLD}Built{RESET} project\n"),
+            String::from_utf8_lossy(&output)
+        );
+    }
+
+    #[test]
+    fn erases_in_progress_state_when_terminal() {
+        let mut output = Vec::new();
+        let mut action = action(true, true);
+        action.write(&mut output);
+        action.state = ActionState::Fail;
+        action.write(&mut output);
+
+        assert_eq!(
+            format!(
+                "{CYAN}{BOLD}Building{RESET} project\n{ERASE_LINE_UP}{RED}{BOLD}Building{RESET} project {RED}{BOLD}failed{RESET}\n"
+            ),
+            String::from_utf8_lossy(&output)
+        );
+    }
+
+    #[test]
+    fn writes_plain_text_when_unstyled() {
+        let mut output = Vec::new();
+        let mut action = action(true, false);
+        action.write(&mut output);
+        action.state = ActionState::Fail;
+        action.write(&mut output);
+
+        let output = String::from_utf8_lossy(&output);
+        assert_eq!("Building project failed\n", output);
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn persists_completed_state() {
+        let mut output = Vec::new();
+        let mut action = action(true, true).persist_completed();
+        action.write(&mut output);
+        action.state = ActionState::Success;
+        action.write(&mut output);
+
+        action.detail = "tests".to_string();
+        action.state = ActionState::InProgress;
+        action.write(&mut output);
+        action.state = ActionState::Fail;
+        action.write(&mut output);
+
+        assert_eq!(
+            format!(
+                "{CYAN}{BOLD}Building{RESET} project\n{ERASE_LINE_UP}{GREEN}{BOLD}Built{RESET} project\n{CYAN}{BOLD}Building{RESET} tests\n{ERASE_LINE_UP}{RED}{BOLD}Building{RESET} tests {RED}{BOLD}failed{RESET}\n"
+            ),
+            String::from_utf8_lossy(&output)
+        );
+    }
+
+    #[test]
+    fn writes_skipped_state() {
+        let mut plain = Vec::new();
+        let mut plain_action = action(false, false);
+        plain_action.set_state(ActionState::Skipped);
+        plain_action.write(&mut plain);
+
+        let mut output = Vec::new();
+        let mut action = action(false, true);
+        action.set_state(ActionState::Skipped);
+        action.write(&mut output);
+
+        assert_eq!(
+            format!("{DIM}Building project skipped{RESET}\n"),
+            String::from_utf8_lossy(&output)
+        );
+        assert_eq!(
+            "Building project skipped\n",
+            String::from_utf8_lossy(&plain)
+        );
+    }
+
+    #[test]
+    fn ticks_spinner() {
+        let mut output = Vec::new();
+        let mut action = action(true, true);
+        action.write(&mut output);
+        action.tick_to(&mut output);
+        action.tick_to(&mut output);
+        action.state = ActionState::Success;
+        action.write(&mut output);
+        action.tick_to(&mut output);
+
+        assert_eq!(
+            format!(
+                "{CYAN}{BOLD}Building{RESET} project\n{ERASE_LINE_UP}{CYAN}{BOLD}⠋ Building{RESET} project\n{ERASE_LINE_UP}{CYAN}{BOLD}⠙ Building{RESET} project\n{ERASE_LINE_UP}{GREEN}{BOLD}Built{RESET} project\n"
+            ),
+            String::from_utf8_lossy(&output)
+        );
+    }
+
+    #[test]
+    fn stops_ticker_when_reported() {
+        let handle = action(false, false).spawn_ticker();
+        // Wait for the first tick, rather than for a fixed time, so a slow thread cannot fail the
+        // test.
+        while handle.lock().spinner_frame.is_none() {
+            thread::sleep(TICK_INTERVAL);
+        }
+
+        let action = Arc::clone(&handle.action);
+        handle.report_success();
+        assert_eq!(1, Arc::strong_count(&action));
+        assert_eq!(
+            ActionState::Success,
+            action.lock().expect("lock should not be poisoned").state
+        );
+    }
+
+    #[test]
+    fn humanizes_durations() {
+        assert_eq!("0ms", humanize(Duration::ZERO));
+        assert_eq!("250ms", humanize(Duration::from_millis(250)));
+        assert_eq!("1.0s", humanize(Duration::from_millis(1000)));
+        assert_eq!("1.3s", humanize(Duration::from_millis(1349)));
+        assert_eq!("59.9s", humanize(Duration::from_millis(59_999)));
+        assert_eq!("1m00s", humanize(Duration::from_secs(60)));
+        assert_eq!("1m04s", humanize(Duration::from_millis(64_500)));
+        assert_eq!("62m05s", humanize(Duration::from_secs(3725)));
+    }
+
+    #[test]
+    fn shows_timing_when_enabled() {
+        let mut action = action(true, false);
+        action.set_state(ActionState::Success);
+        assert_eq!("Built project", action.message());
+
+        let mut action = action.with_timing();
+        action.elapsed = Some(Duration::from_millis(1300));
+        assert_eq!("Built project (1.3s)", action.message());
+
+        action.state = ActionState::Fail;
+        assert_eq!("Building project failed (1.3s)", action.message());
+
+        action.set_state(ActionState::InProgress);
+        assert_eq!(None, action.elapsed);
+    }
+
+    #[test]
+    fn fits_progress_bar_to_width() {
+        assert_eq!("[####----] 50% (5/10)", progress_bar(5, 10, 21));
+        assert_eq!("[--------] 0% (0/10)", progress_bar(0, 10, 20));
+        assert_eq!("[##########] 100% (10/10)", progress_bar(10, 10, 25));
+        assert_eq!("[#] 100% (0/0)", progress_bar(0, 0, 14));
+        assert_eq!("50% (5/10)", progress_bar(5, 10, 12));
+
+        assert_eq!("", progress_bar(5, 10, 9));
+
+        for width in 0..60 {
+            let bar = progress_bar(3, 7, width);
+            assert!(bar.len() <= width, "{bar:?} is wider than {width}");
+        }
+    }
+
+    #[test]
+    fn clears_progress_when_completed() {
+        let mut output = Vec::new();
+        let mut action = action(true, false).with_total(10);
+        action.progress = Some((5, 10));
+        assert!(action.message().starts_with("Building project ["));
+        assert!(action.message().ends_with("] 50% (5/10)"));
+
+        action.state = ActionState::Success;
+        action.write(&mut output);
+        assert_eq!("Built project\n", String::from_utf8_lossy(&output));
+    }
+
+    #[test]
+    fn fits_in_progress_message_to_width() {
+        let mut action = action(true, true).with_total(10);
+        action.detail = "a project with a long name".to_string();
+        action.progress = Some((5, 10));
+        action.spinner_frame = Some(0);
+
+        let message = action.message_within(80);
+        assert!(message.starts_with(&format!("{CYAN}{BOLD}⠋ Building{RESET}")));
+        assert!(message.ends_with("] 50% (5/10)"));
+        assert_eq!(80, visible_width(&message));
+
+        // The bar is dropped, then the message is truncated, as the width shrinks.
+        assert!(!action.message_within(40).contains("50%"));
+        for width in 0..100 {
+            let message = action.message_within(width);
+            assert!(
+                visible_width(&message) <= width,
+                "{message:?} is wider than {width}"
+            );
+        }
+
+        action.set_state(ActionState::Success);
+        assert_eq!(
+            format!("{GREEN}{BOLD}Built{RESET} a project with a long name"),
+            action.message_within(10)
+        );
     }
 }
@@ -0,0 +1,259 @@
+//! Reporting many actions that are performed in parallel, each on their own line.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use std::{
+    io::{IsTerminal, Write, stderr},
+    sync::{Mutex, PoisonError},
+};
+
+use ts_ansi::style::ERASE_LINE_UP;
+
+use crate::{
+    action::{Action, ActionState},
+    ansi_support::styles_stderr,
+};
+
+/// The lines of an action area, shared between the threads reporting to it.
+struct AreaState {
+    /// The action on each line of the area.
+    actions: Vec<Action>,
+    /// The number of lines that are currently drawn to the writer.
+    drawn: usize,
+    /// Is the writer an interactive terminal, if not, only the final state of each action is
+    /// written.
+    is_interactive: bool,
+    /// The writer the area is drawn to.
+    writer: Box<dyn Write + Send>,
+}
+impl AreaState {
+    /// Redraw the area after the action on a line has changed.
+    ///
+    /// All IO errors are ignored.
+    fn redraw(&mut self, changed: usize) {
+        #![expect(
+            unused_must_use,
+            reason = "displaying output is a non-critical part of the program, so this should not
+            panic, additionally, I don't want to have to think about the errors when calling this"
+        )]
+
+        if self.is_interactive {
+            for _ in 0..self.drawn {
+                self.writer.write_all(ERASE_LINE_UP.as_bytes());
+            }
+            for action in &self.actions {
+                writeln!(self.writer, "{}", action.message());
+            }
+            self.drawn = self.actions.len();
+        } else if let Some(action) = self.actions.get(changed)
+            && action.state() != ActionState::InProgress
+        {
+            writeln!(self.writer, "{}", action.message());
+        }
+
+        self.writer.flush();
+    }
+}
+
+/// A region of `stderr` where many actions, possibly on different threads, each own a line.
+///
+/// Writes to the region are coordinated through a shared lock, so actions updating their line from
+/// different threads do not interleave or erase each other's lines. The area can be cloned to share
+/// it between threads.
+///
+/// ## Limitations
+/// * Anything else writing to `stdout`/`stderr` while the area is in use will be erased.
+/// * If `stderr` is not a terminal or does not process ANSI escape sequences, only the final
+///   success or failure of each action is printed.
+#[derive(Clone)]
+pub struct ActionArea {
+    /// Does the writer process ANSI escape sequences, if not, actions are written without styling.
+    is_styled: bool,
+    /// The lines of the area.
+    state: Arc<Mutex<AreaState>>,
+}
+impl ActionArea {
+    /// Add and report a new in progress action on its own line at the bottom of the area.
+    pub fn action<S1: ToString, S2: ToString, S3: ToString>(
+        &self,
+        actioning_verb: S1,
+        actioned_verb: S2,
+        detail: S3,
+    ) -> AreaAction {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let line = state.actions.len();
+        state.actions.push(Action::unreported(
+            actioning_verb,
+            actioned_verb,
+            detail,
+            self.is_styled,
+        ));
+        state.redraw(line);
+
+        AreaAction {
+            area: self.clone(),
+            line,
+        }
+    }
+
+    /// Returns the current message on each line of the area.
+    pub fn lines(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.actions.iter().map(Action::message).collect()
+    }
+
+    /// Create a new, empty, action area on `stderr`.
+    pub fn new() -> Self {
+        let is_styled = styles_stderr();
+        Self::with_writer(
+            Box::new(stderr()),
+            stderr().is_terminal() && is_styled,
+            is_styled,
+        )
+    }
+
+    /// Set the state of the action on a line and redraw the area.
+    fn set_state(&self, line: usize, action_state: ActionState) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(action) = state.actions.get_mut(line) {
+            action.set_state(action_state);
+        }
+        state.redraw(line);
+    }
+
+    /// Create a new, empty, action area on a writer.
+    fn with_writer(writer: Box<dyn Write + Send>, is_interactive: bool, is_styled: bool) -> Self {
+        Self {
+            is_styled,
+            state: Arc::new(Mutex::new(AreaState {
+                actions: Vec::new(),
+                drawn: 0,
+                is_interactive,
+                writer,
+            })),
+        }
+    }
+}
+impl Default for ActionArea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An action that owns a line of an [`ActionArea`].
+#[derive(Clone)]
+pub struct AreaAction {
+    /// The area the action is in.
+    area: ActionArea,
+    /// The line of the area the action owns.
+    line: usize,
+}
+impl AreaAction {
+    /// Report the action as failed.
+    pub fn report_fail(&self) {
+        self.area.set_state(self.line, ActionState::Fail);
+    }
+
+    /// Report the action as skipped.
+    pub fn report_skipped(&self) {
+        self.area.set_state(self.line, ActionState::Skipped);
+    }
+
+    /// Report the action as a success.
+    pub fn report_success(&self) {
+        self.area.set_state(self.line, ActionState::Success);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+    use std::{
+        io::{self, Write},
+        sync::Mutex,
+        thread,
+    };
+
+    use ts_ansi::style::*;
+
+    use crate::action_area::ActionArea;
+
+    /// A writer that can be read after being moved into an area.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+    impl Write for SharedBuffer {
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0
+                .lock()
+                .expect("lock should not be poisoned")
+                .write(buf)
+        }
+    }
+
+    #[test]
+    fn keeps_each_threads_line() {
+        let output = SharedBuffer::default();
+        let area = ActionArea::with_writer(Box::new(output.clone()), true, true);
+
+        let handles: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|detail| {
+                let action = area.action("Building", "Built", detail);
+                thread::spawn(move || action.report_success())
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("thread should not panic");
+        }
+
+        let first = format!("{GREEN}{BOLD}Built{RESET} first");
+        let second = format!("{GREEN}{BOLD}Built{RESET} second");
+        assert_eq!(vec![first.clone(), second.clone()], area.lines());
+
+        let output = String::from_utf8(
+            output
+                .0
+                .lock()
+                .expect("lock should not be poisoned")
+                .clone(),
+        )
+        .expect("output should be UTF-8");
+        assert!(
+            output.ends_with(&format!(
+                "{ERASE_LINE_UP}{ERASE_LINE_UP}{first}\n{second}\n"
+            )),
+            "unexpected output {output:?}"
+        );
+    }
+
+    #[test]
+    fn only_writes_final_states_when_not_interactive() {
+        let output = SharedBuffer::default();
+        let area = ActionArea::with_writer(Box::new(output.clone()), false, false);
+
+        let first = area.action("Building", "Built", "first");
+        let second = area.action("Building", "Built", "second");
+        second.report_fail();
+        first.report_success();
+
+        let output = String::from_utf8(
+            output
+                .0
+                .lock()
+                .expect("lock should not be poisoned")
+                .clone(),
+        )
+        .expect("output should be UTF-8");
+        assert_eq!("Building second failed\nBuilt first\n", output);
+    }
+}
@@ -0,0 +1,98 @@
+//! Enable ANSI escape sequence support on consoles that do not process them by default.
+
+#[cfg(windows)]
+/// Bindings to the Windows console API.
+mod windows {
+    use core::ffi::c_void;
+    use std::{
+        io::{self, stderr},
+        os::windows::io::AsRawHandle,
+    };
+
+    /// Console mode flag to process ANSI escape sequences.
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        /// <https://learn.microsoft.com/en-us/windows/console/getconsolemode>
+        fn GetConsoleMode(handle: *mut c_void, mode: *mut u32) -> i32;
+        /// <https://learn.microsoft.com/en-us/windows/console/setconsolemode>
+        fn SetConsoleMode(handle: *mut c_void, mode: u32) -> i32;
+    }
+
+    /// Enable virtual terminal processing for the `stderr` console.
+    pub(super) fn enable_virtual_terminal_processing() -> io::Result<()> {
+        let handle = stderr().as_raw_handle();
+
+        let mut mode = 0;
+        // SAFETY: `handle` is the process' `stderr` handle, and `mode` is a valid pointer to write
+        // the console mode to.
+        if unsafe { GetConsoleMode(handle, &mut mode) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+            return Ok(());
+        }
+
+        // SAFETY: `handle` is the process' `stderr` handle.
+        if unsafe { SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+}
+
+use std::{io, sync::OnceLock};
+
+use ts_ansi::{Stream, should_style};
+
+/// Enable processing of ANSI escape sequences written to `stderr`.
+///
+/// On Windows, this enables virtual terminal processing for the console. Older consoles that do
+/// not support virtual terminal processing, and handles that are not consoles, return an error,
+/// and output should not be styled. On all other platforms, this always succeeds.
+pub fn enable_ansi_support() -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        windows::enable_virtual_terminal_processing()
+    }
+    #[cfg(not(windows))]
+    {
+        Ok(())
+    }
+}
+
+/// Returns if ANSI escape sequences written to `stderr` are processed, enabling support on first
+/// use.
+pub(crate) fn supports_ansi() -> bool {
+    /// If ANSI escape sequences are supported.
+    static SUPPORTS_ANSI: OnceLock<bool> = OnceLock::new();
+    *SUPPORTS_ANSI.get_or_init(|| enable_ansi_support().is_ok())
+}
+
+/// Returns if output written to `stderr` should be styled, ANSI escape sequences must be processed
+/// and styling must not be disabled, see [`should_style`].
+pub(crate) fn styles_stderr() -> bool {
+    supports_ansi() && should_style(Stream::Stderr)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::enable_ansi_support;
+
+    #[test]
+    #[cfg(windows)]
+    fn enables_ansi_support() {
+        // Test harnesses may not have a console, so either result is acceptable.
+        let _ = enable_ansi_support();
+        let _ = enable_ansi_support();
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn always_supports_ansi() {
+        assert!(enable_ansi_support().is_ok());
+    }
+}
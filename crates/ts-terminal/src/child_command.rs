@@ -7,6 +7,9 @@ use std::{
     thread,
 };
 
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncWriteExt;
+
 /// Error variants for using a child command.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -95,3 +98,93 @@ pub fn process_using_child<C: AsRef<OsStr>, I: IntoIterator<Item = S>, S: AsRef<
 
     Ok(output.stdout)
 }
+
+/// Write `data` to a child process' `stdin`, and return the process' `stdout`, without blocking
+/// the current thread.
+///
+/// ## Panics
+/// * If handle to child's `stdin` could not be taken.
+#[cfg(feature = "tokio")]
+pub async fn process_using_child_async<
+    C: AsRef<OsStr>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+>(
+    command: C,
+    args: I,
+    data: &[u8],
+) -> Result<Vec<u8>, ChildCommandError> {
+    let mut child = tokio::process::Command::new(command)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|source| ChildCommandError::SpawnChild { source })?;
+
+    let mut stdin = child.stdin.take().expect("stdin handle to be present");
+    // The writer owns `stdin`, so it is closed once all data has been written.
+    let writer = async move { stdin.write_all(data).await };
+
+    let (write_result, output) = tokio::join!(writer, child.wait_with_output());
+    let output = output.map_err(|source| ChildCommandError::ReadOutput { source })?;
+    write_result.map_err(|source| ChildCommandError::WriteToStdin { source })?;
+
+    if !output.status.success() {
+        return Err(ChildCommandError::UnsuccessfulStatus {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(all(feature = "tokio", unix))]
+    use core::iter;
+
+    #[cfg(all(feature = "tokio", unix))]
+    use crate::{ChildCommandError, process_using_child_async};
+
+    #[cfg(all(feature = "tokio", unix))]
+    /// Run a future to completion on a current thread runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime should build")
+            .block_on(future)
+    }
+
+    #[test]
+    #[cfg(all(feature = "tokio", unix))]
+    fn pipes_through_child() {
+        let data = b"some data to pipe through the child".repeat(4096);
+        let output = block_on(process_using_child_async(
+            "cat",
+            iter::empty::<&str>(),
+            &data,
+        ))
+        .expect("piping through `cat` should succeed");
+        assert_eq!(data, output);
+    }
+
+    #[test]
+    #[cfg(all(feature = "tokio", unix))]
+    fn reports_unsuccessful_status() {
+        let result = block_on(process_using_child_async(
+            "sh",
+            ["-c", "echo failure >&2; exit 3"],
+            b"",
+        ));
+        match result {
+            Err(ChildCommandError::UnsuccessfulStatus { status, stderr }) => {
+                assert_eq!(Some(3), status.code());
+                assert_eq!("failure\n", stderr);
+            }
+            _ => panic!("expected an unsuccessful status, got {result:?}"),
+        }
+    }
+}
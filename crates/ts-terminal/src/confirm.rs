@@ -0,0 +1,86 @@
+//! Prompting the user for confirmation.
+
+use alloc::string::String;
+use std::io::{self, BufRead, Write, stderr, stdin};
+
+/// Prompt the user on `stderr` for confirmation, reading their answer from `stdin`.
+///
+/// Answers of `y`, `yes`, `n`, and `no` are case-insensitive, an empty answer returns `default`,
+/// and any other answer prompts again.
+pub fn confirm(prompt: &str, default: bool) -> io::Result<bool> {
+    confirm_with(prompt, default, &mut stdin().lock(), &mut stderr().lock())
+}
+
+/// Prompt the user on `writer` for confirmation, reading their answer from `reader`.
+///
+/// If `reader` has no more input, `default` is returned.
+fn confirm_with<R: BufRead, W: Write>(
+    prompt: &str,
+    default: bool,
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<bool> {
+    let options = if default { "Y/n" } else { "y/N" };
+
+    loop {
+        write!(writer, "{prompt} ({options}): ")?;
+        writer.flush()?;
+
+        let mut buffer = String::new();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(default);
+        }
+
+        if let Some(answer) = parse_answer(&buffer, default) {
+            return Ok(answer);
+        }
+    }
+}
+
+/// Parse an answer to a confirmation prompt, returns `None` if the answer is not recognised.
+fn parse_answer(answer: &str, default: bool) -> Option<bool> {
+    match answer.trim().to_lowercase().as_str() {
+        "" => Some(default),
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::confirm::{confirm_with, parse_answer};
+
+    #[test]
+    fn parses_answers() {
+        assert_eq!(Some(true), parse_answer("y\n", false));
+        assert_eq!(Some(true), parse_answer("  YES  ", false));
+        assert_eq!(Some(false), parse_answer("N\r\n", true));
+        assert_eq!(Some(false), parse_answer("no", true));
+        assert_eq!(Some(true), parse_answer("\n", true));
+        assert_eq!(Some(false), parse_answer("", false));
+        assert_eq!(None, parse_answer("maybe", true));
+    }
+
+    #[test]
+    fn reprompts_on_unrecognised_answer() {
+        let mut output = Vec::new();
+        let answer = confirm_with(
+            "Overwrite",
+            true,
+            &mut b"maybe\nn\n".as_slice(),
+            &mut output,
+        )
+        .expect("confirm should succeed");
+        assert!(!answer);
+        assert_eq!(
+            "Overwrite (Y/n): Overwrite (Y/n): ",
+            String::from_utf8_lossy(&output)
+        );
+
+        let mut output = Vec::new();
+        let answer = confirm_with("Overwrite", false, &mut b"".as_slice(), &mut output)
+            .expect("confirm should succeed");
+        assert!(!answer);
+    }
+}
@@ -5,7 +5,16 @@
 extern crate alloc;
 
 mod action;
+mod action_area;
+mod ansi_support;
 mod child_command;
+mod confirm;
+mod terminal_size;
 
-pub use action::{Action, ActionResult};
+pub use action::{Action, ActionHandle, ActionResult, SkippableActionResult};
+pub use action_area::{ActionArea, AreaAction};
+pub use ansi_support::enable_ansi_support;
+#[cfg(feature = "tokio")]
+pub use child_command::process_using_child_async;
 pub use child_command::{ChildCommandError, process_using_child};
+pub use confirm::confirm;
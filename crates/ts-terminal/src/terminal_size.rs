@@ -0,0 +1,164 @@
+//! Query the size of the terminal that `stderr` is connected to.
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+/// Bindings to the terminal `ioctl`.
+mod unix {
+    use core::ffi::{c_int, c_ulong, c_ushort};
+
+    /// The file descriptor of `stderr`.
+    const STDERR_FILENO: c_int = 2;
+
+    /// The `ioctl` request to get the window size of a terminal.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    /// The `ioctl` request to get the window size of a terminal.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const TIOCGWINSZ: c_ulong = 0x4008_7468;
+
+    /// The window size of a terminal, `struct winsize`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct WindowSize {
+        /// The rows in characters.
+        rows: c_ushort,
+        /// The columns in characters.
+        columns: c_ushort,
+        /// The width in pixels, unused.
+        x_pixels: c_ushort,
+        /// The height in pixels, unused.
+        y_pixels: c_ushort,
+    }
+
+    unsafe extern "C" {
+        /// <https://man7.org/linux/man-pages/man2/ioctl.2.html>
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    /// Returns the width in columns of the terminal `stderr` is connected to.
+    pub(super) fn stderr_width() -> Option<usize> {
+        let mut size = WindowSize::default();
+
+        // SAFETY: `TIOCGWINSZ` writes a `struct winsize` to the pointer, which points to a valid
+        // `WindowSize` with the same layout.
+        if unsafe { ioctl(STDERR_FILENO, TIOCGWINSZ, core::ptr::from_mut(&mut size)) } != 0 {
+            return None;
+        }
+
+        Some(usize::from(size.columns))
+    }
+}
+
+#[cfg(windows)]
+/// Bindings to the Windows console API.
+mod windows {
+    use core::ffi::c_void;
+    use std::{io::stderr, os::windows::io::AsRawHandle};
+
+    /// A position in a console screen buffer, `COORD`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct Coord {
+        /// The column.
+        x: i16,
+        /// The row.
+        y: i16,
+    }
+
+    /// A rectangle in a console screen buffer, `SMALL_RECT`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct SmallRect {
+        /// The left column.
+        left: i16,
+        /// The top row.
+        top: i16,
+        /// The right column, inclusive.
+        right: i16,
+        /// The bottom row, inclusive.
+        bottom: i16,
+    }
+
+    /// Information about a console screen buffer, `CONSOLE_SCREEN_BUFFER_INFO`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct ScreenBufferInfo {
+        /// The size of the screen buffer.
+        size: Coord,
+        /// The position of the cursor.
+        cursor_position: Coord,
+        /// The attributes of written characters.
+        attributes: u16,
+        /// The visible window of the screen buffer.
+        window: SmallRect,
+        /// The maximum size of the window.
+        maximum_window_size: Coord,
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        /// <https://learn.microsoft.com/en-us/windows/console/getconsolescreenbufferinfo>
+        fn GetConsoleScreenBufferInfo(handle: *mut c_void, info: *mut ScreenBufferInfo) -> i32;
+    }
+
+    /// Returns the width in columns of the console window `stderr` is connected to.
+    pub(super) fn stderr_width() -> Option<usize> {
+        let handle = stderr().as_raw_handle();
+        let mut info = ScreenBufferInfo::default();
+
+        // SAFETY: `handle` is the process' `stderr` handle, and `info` is a valid pointer to write
+        // the screen buffer information to.
+        if unsafe { GetConsoleScreenBufferInfo(handle, &mut info) } == 0 {
+            return None;
+        }
+
+        usize::try_from(i32::from(info.window.right) - i32::from(info.window.left) + 1).ok()
+    }
+}
+
+use std::env;
+
+/// Returns the width in columns of the terminal `stderr` is connected to. If `stderr` is not a
+/// terminal, the `COLUMNS` environment variable is used.
+pub(crate) fn terminal_width() -> Option<usize> {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))]
+    let width = unix::stderr_width();
+    #[cfg(windows)]
+    let width = windows::stderr_width();
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        windows
+    )))]
+    let width = None;
+
+    width.filter(|&columns| columns > 0).or_else(|| {
+        env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .filter(|&columns| columns > 0)
+    })
+}